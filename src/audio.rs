@@ -0,0 +1,136 @@
+//! Sounds the player can hear from the office, worked out from the game's events so the frontend
+//! only has to play them
+
+use serde::{Deserialize, Serialize};
+
+use crate::{events::GameEvent, map::RoomId, Door, GameState};
+
+/// The kinds of sounds the office can hear
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sound {
+    /// An enemy walking around just outside the office
+    Footsteps,
+    /// An enemy banging on a door
+    Bang,
+}
+
+/// A sound to play, along with where it's coming from
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AudioCue {
+    /// What to play
+    pub sound: Sound,
+    /// Which side the sound comes from, from -1.0 for hard left to 1.0 for hard right
+    pub pan: f32,
+    /// How loud to play it, from 0.0 to 1.0. Halves with every room between it and the office
+    pub loudness: f32,
+}
+
+impl AudioCue {
+    /// The sound the player hears because of an event, if it's one they can hear at all
+    pub fn from_event(event: &GameEvent, state: &GameState) -> Option<Self> {
+        match *event {
+            GameEvent::EnemyMoved { to, .. } => Self::footsteps(to, state),
+            GameEvent::DoorBanged { door } => Some(Self {
+                sound: Sound::Bang,
+                pan: Self::door_pan(door),
+                loudness: 1.0,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Footsteps for an enemy moving into a room, if it's at or next to one of the office's
+    /// entrances. Rooms next to both entrances sound like they're straight ahead
+    fn footsteps(room: RoomId, state: &GameState) -> Option<Self> {
+        let office = &state.office;
+        if room == office.root {
+            return None;
+        }
+
+        let near = |entrance: RoomId| {
+            room == entrance || state.map.0[room].connections().contains(&entrance)
+        };
+        let pan = match (near(office.left), near(office.right)) {
+            (false, false) => return None,
+            (true, false) => Self::door_pan(Door::Left),
+            (false, true) => Self::door_pan(Door::Right),
+            (true, true) => 0.0,
+        };
+
+        let distance = state.map.distances_to(office.root).get(room).copied()?;
+
+        Some(Self {
+            sound: Sound::Footsteps,
+            pan,
+            loudness: 0.5f32.powi(distance as i32 - 1),
+        })
+    }
+
+    /// How far to pan a sound coming from behind a door
+    fn door_pan(door: Door) -> f32 {
+        match door {
+            Door::Left => -1.0,
+            Door::Right => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{events::GameEvent, GameState};
+
+    use super::{AudioCue, Sound};
+
+    #[test]
+    fn footsteps_get_quieter_further_from_the_office() {
+        // A fixed map, laid out with rooms both next to an entrance and further off
+        let (game, [enemy], _) = GameState::test_night(0xA0D10);
+        let office = &game.office;
+
+        let at_door = AudioCue::from_event(
+            &GameEvent::EnemyMoved {
+                enemy,
+                from: None,
+                to: office.left,
+            },
+            &game,
+        )
+        .unwrap();
+        assert_eq!(at_door.sound, Sound::Footsteps);
+        assert_eq!(at_door.pan, -1.0);
+        assert_eq!(at_door.loudness, 1.0);
+
+        let hallway = game.map.0[office.right]
+            .connections()
+            .iter()
+            .copied()
+            .find(|room| *room != office.root && *room != office.left)
+            .unwrap();
+        let nearby = AudioCue::from_event(
+            &GameEvent::EnemyMoved {
+                enemy,
+                from: None,
+                to: hallway,
+            },
+            &game,
+        )
+        .unwrap();
+        assert_eq!(nearby.pan, 1.0);
+        assert_eq!(nearby.loudness, 0.5);
+
+        let far = game
+            .map
+            .distances_to(office.root)
+            .iter()
+            .find(|(_, distance)| **distance > 2)
+            .map(|(room, _)| room)
+            .unwrap();
+        let event = GameEvent::EnemyMoved {
+            enemy,
+            from: None,
+            to: far,
+        };
+        assert_eq!(AudioCue::from_event(&event, &game), None);
+    }
+}
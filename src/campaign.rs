@@ -0,0 +1,285 @@
+//! Progression through the five nights of a campaign, with enemies getting more numerous and more
+//! restless each night, plus custom nights where every enemy's aggression is picked by hand
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use slotmap::SlotMap;
+
+use crate::{
+    enemies::{
+        roster::{BehaviorRegistry, Roster},
+        EnemyId, Freak, MAX_AGGRESSION,
+    },
+    Door,
+};
+
+/// How many nights make up a full campaign
+pub const NIGHTS: u8 = 5;
+
+/// How much faster enemy cooldowns get with each night, as a fraction of their first night's
+/// cooldown
+const COOLDOWN_SCALE_PER_NIGHT: f64 = 0.1;
+
+/// A player's progress through the campaign
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Campaign {
+    /// The night currently being played, starting from 1
+    night: u8,
+    /// The furthest night the player has unlocked
+    unlocked: u8,
+    /// Every enemy's aggression level by name, if this is a custom night rather than a campaign
+    /// night
+    custom_levels: Option<BTreeMap<String, u8>>,
+    /// Every enemy that can show up, and on which night
+    roster: Roster,
+    /// How the player has kept the doors over every night won so far
+    #[serde(default)]
+    habits: DoorHabits,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self::at_night(1)
+    }
+}
+
+impl Campaign {
+    /// Creates a campaign starting at the given night
+    pub fn at_night(night: u8) -> Self {
+        let night = night.clamp(1, NIGHTS);
+        Self {
+            night,
+            unlocked: night,
+            custom_levels: None,
+            roster: Roster::default(),
+            habits: DoorHabits::default(),
+        }
+    }
+
+    /// Creates a custom night outside of the campaign where each enemy's aggression level is
+    /// given by name. Enemies that aren't named stay asleep
+    pub fn custom_night(levels: BTreeMap<String, u8>) -> Self {
+        Self {
+            custom_levels: Some(levels),
+            ..Self::default()
+        }
+    }
+
+    /// Swaps out which enemies can show up for a different roster
+    pub fn with_roster(mut self, roster: Roster) -> Self {
+        self.roster = roster;
+        self
+    }
+
+    /// The night currently being played
+    pub fn night(&self) -> u8 {
+        self.night
+    }
+
+    /// Marks the current night as won, unlocking the next one
+    pub fn win_night(&mut self) {
+        if self.custom_levels.is_none() {
+            self.unlocked = self.unlocked.max((self.night + 1).min(NIGHTS + 1));
+        }
+    }
+
+    /// How the player has kept the doors over every night won so far
+    pub fn habits(&self) -> DoorHabits {
+        self.habits
+    }
+
+    /// Remembers how the player kept the doors, carrying it into the nights that follow
+    pub fn remember_habits(&mut self, habits: DoorHabits) {
+        self.habits = habits;
+    }
+
+    /// Has every night been won?
+    pub fn is_complete(&self) -> bool {
+        self.unlocked > NIGHTS
+    }
+
+    /// Moves on to the next night if it has been unlocked, returning whether we did
+    pub fn advance(&mut self) -> bool {
+        if self.night < NIGHTS && self.unlocked > self.night {
+            self.night += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// How much enemy cooldowns are scaled by tonight
+    pub fn cooldown_scale(&self) -> f64 {
+        1.0 - COOLDOWN_SCALE_PER_NIGHT * (self.night - 1) as f64
+    }
+
+    /// Creates every enemy that takes part in tonight's night from the given registry. Custom
+    /// levels for enemies that aren't on the roster, or above `MAX_AGGRESSION`, are rejected
+    pub fn roster(&self, registry: &BehaviorRegistry) -> Result<SlotMap<EnemyId, Freak>, String> {
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+
+        for (name, level) in self.custom_levels.iter().flatten() {
+            if !self
+                .roster
+                .entries()
+                .iter()
+                .any(|entry| entry.name == *name)
+            {
+                return Err(format!(
+                    "There's no enemy called `{name}` to set the level of"
+                ));
+            }
+            if *level > MAX_AGGRESSION {
+                return Err(format!(
+                    "`{name}`'s level should be at most {MAX_AGGRESSION}, not {level}"
+                ));
+            }
+        }
+
+        for entry in self.roster.entries() {
+            match &self.custom_levels {
+                Some(levels) => {
+                    let level = levels.get(&entry.name).copied().unwrap_or(0);
+                    enemies.insert(entry.build(registry)?.with_level(level));
+                }
+                None if self.night >= entry.night => {
+                    enemies.insert(entry.build(registry)?.scale_cooldown(self.cooldown_scale()));
+                }
+                None => {}
+            }
+        }
+
+        Ok(enemies)
+    }
+}
+
+/// How many ticks the player has spent with each door closed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DoorHabits {
+    /// Ticks spent with the left door closed
+    pub left: u64,
+    /// Ticks spent with the right door closed
+    pub right: u64,
+}
+
+impl DoorHabits {
+    /// Counts another tick of the doors being the way they are
+    pub fn record(&mut self, left_closed: bool, right_closed: bool) {
+        self.left += left_closed as u64;
+        self.right += right_closed as u64;
+    }
+
+    /// The share of all closed-door time spent with the given door closed, from 0 to 1. With
+    /// nothing recorded yet, both doors are treated the same
+    pub fn share(&self, door: Door) -> f64 {
+        let closed = match door {
+            Door::Left => self.left,
+            Door::Right => self.right,
+        };
+
+        (closed + 1) as f64 / (self.left + self.right + 2) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        config::NightConfig,
+        enemies::{roster::BehaviorRegistry, State},
+        Game,
+    };
+
+    use super::{Campaign, NIGHTS};
+
+    #[test]
+    fn nights_only_advance_once_won() {
+        let mut campaign = Campaign::default();
+        assert!(!campaign.advance());
+
+        for night in 1..NIGHTS {
+            campaign.win_night();
+            assert!(campaign.advance());
+            assert_eq!(campaign.night(), night + 1);
+        }
+
+        campaign.win_night();
+        assert!(campaign.is_complete());
+        assert!(!campaign.advance());
+    }
+
+    #[test]
+    fn later_nights_are_harder() {
+        let registry = BehaviorRegistry::default();
+        let first = Campaign::at_night(1);
+        let last = Campaign::at_night(NIGHTS);
+
+        assert!(last.roster(&registry).unwrap().len() > first.roster(&registry).unwrap().len());
+        assert!(last.cooldown_scale() < first.cooldown_scale());
+    }
+
+    #[test]
+    fn winning_a_night_lets_the_game_continue_to_the_next() {
+        let config = NightConfig {
+            ticks_per_hour: 10,
+            hours_to_win: 1,
+            ..Default::default()
+        };
+        let mut game = Game::with_night_config(99, config);
+
+        assert!(!game.advance_night());
+        while !game.tick() {}
+
+        assert!(game.advance_night());
+        assert_eq!(game.current_night(), 2);
+        assert_eq!(game.get_time(), 12);
+    }
+
+    #[test]
+    fn door_habits_carry_over_between_nights() {
+        let config = NightConfig {
+            ticks_per_hour: 10,
+            hours_to_win: 1,
+            ..Default::default()
+        };
+        let mut game = Game::with_night_config(7, config);
+
+        game.toggle_left();
+        while !game.tick() {}
+        assert!(game.advance_night());
+
+        let habits = game.state.door_habits();
+        assert!(habits.left > 0);
+        assert_eq!(habits.right, 0);
+    }
+
+    #[test]
+    fn level_zero_enemies_never_wake_up() {
+        let levels = BTreeMap::from([("teller".to_string(), 20)]);
+        let mut game = Game::with_levels(4, levels).expect("Start custom night");
+
+        for _ in 0..5000 {
+            game.tick();
+        }
+
+        for (_, enemy) in &game.enemies {
+            let expected = if enemy.get_name() == "teller" {
+                State::Moving
+            } else {
+                State::Dormant
+            };
+            assert_eq!(enemy.get_state(), expected);
+        }
+    }
+
+    #[test]
+    fn custom_nights_reject_unknown_enemies_and_levels() {
+        let typo = BTreeMap::from([("tellr".to_string(), 20)]);
+        assert!(Game::with_levels(4, typo).is_err());
+
+        let too_high = BTreeMap::from([("teller".to_string(), 21)]);
+        assert!(Game::with_levels(4, too_high).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Per-night tuning of power and time, so a night can be adjusted without recompiling
+
+use serde::{Deserialize, Serialize};
+
+/// The power budget, draw rates and length of a single night. Any field left out when
+/// deserializing falls back to its default
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NightConfig {
+    /// How much power a door being closed draws
+    pub power_draw_door: i32,
+    /// How much power is idly drawn
+    pub default_power_draw: i32,
+    /// How much power being on the cameras draws
+    pub camera_on_draw: i32,
+    /// How much power each door's light draws while it's on
+    pub light_draw: i32,
+    /// How much power you start with
+    pub initial_power: i32,
+    /// How many game ticks make up an hour
+    pub ticks_per_hour: u64,
+    /// How many hours do we need to survive
+    pub hours_to_win: u64,
+}
+
+impl Default for NightConfig {
+    fn default() -> Self {
+        Self {
+            power_draw_door: 75,
+            default_power_draw: 5,
+            camera_on_draw: 15,
+            light_draw: 15,
+            initial_power: 500_000,
+            ticks_per_hour: 1800,
+            hours_to_win: 6,
+        }
+    }
+}
+
+impl NightConfig {
+    /// Checks the config makes for a playable night, since configs can come from untrusted JSON
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ticks_per_hour == 0 {
+            return Err("Night config's `ticks_per_hour` should be above 0".to_string());
+        }
+        if self.hours_to_win == 0 {
+            return Err("Night config's `hours_to_win` should be above 0".to_string());
+        }
+        if self.ticks_per_hour.checked_mul(self.hours_to_win).is_none() {
+            return Err("Night config's night is too long to count the ticks of".to_string());
+        }
+        if self.initial_power <= 0 {
+            return Err("Night config's `initial_power` should be above 0".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// How many game ticks we need to win
+    pub fn ticks_to_win(&self) -> u64 {
+        self.ticks_per_hour * self.hours_to_win
+    }
+}
@@ -4,13 +4,21 @@ use std::ops::Range;
 
 use action::{Action, EnemyBehavior};
 use rand::Rng;
+use roster::BehaviorRegistry;
+use serde::{Deserialize, Serialize};
 use slotmap::new_key_type;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::GameState;
+use crate::{events::GameEvent, schedule::Trigger, GameState};
 
 pub mod action;
+pub mod effects;
 pub mod impls;
+pub mod roster;
+pub mod tree;
+
+/// The highest aggression level an enemy can be set to
+pub const MAX_AGGRESSION: u8 = 20;
 
 new_key_type! {
     /// An enemy's ID for usage in the room HashMap
@@ -21,7 +29,7 @@ new_key_type! {
 /// An enemy and all of its related behavioral metadata
 pub struct Freak {
     /// The enemy's name
-    name: &'static str,
+    name: String,
     /// The enemies state, whether they're on the move or not
     state: State,
     /// The (range in) amount of ticks before another behavior is performed (randomly selected each
@@ -29,39 +37,111 @@ pub struct Freak {
     cooldown: Range<u64>,
     /// An enemies behavior is specific to them
     behavior: Box<dyn EnemyBehavior>,
+    /// How aggressive the enemy is, from 0 (never wakes up) to `MAX_AGGRESSION`
+    level: u8,
+    /// The spec the enemy's behavior was built from, if it came from a roster. Lets the enemy be
+    /// rebuilt from a save or copied
+    spec: Option<serde_json::Value>,
+    /// Where the enemy goes when the player shuts the door on its attack
+    retreat: Retreat,
 }
 
 impl Freak {
     /// Creates a new enemy
-    pub fn new<BEHAVIOR: EnemyBehavior + 'static>(
-        name: &'static str,
+    pub fn new<NAME: Into<String>, BEHAVIOR: EnemyBehavior + 'static>(
+        name: NAME,
         cooldown: Range<u64>,
         behavior: BEHAVIOR,
+    ) -> Self {
+        Self::from_boxed(name, cooldown, Box::new(behavior))
+    }
+
+    /// Creates a new enemy from an already boxed behavior
+    pub fn from_boxed<NAME: Into<String>>(
+        name: NAME,
+        cooldown: Range<u64>,
+        behavior: Box<dyn EnemyBehavior>,
     ) -> Self {
         Self {
-            name,
+            name: name.into(),
             state: State::Dormant,
             cooldown,
-            behavior: Box::new(behavior),
+            behavior,
+            level: MAX_AGGRESSION,
+            spec: None,
+            retreat: Retreat::default(),
         }
     }
 
+    /// Creates a new enemy whose behavior is built from a spec through the behavior registry
+    pub fn from_spec<NAME: Into<String>>(
+        name: NAME,
+        cooldown: Range<u64>,
+        spec: serde_json::Value,
+        registry: &BehaviorRegistry,
+    ) -> Result<Self, String> {
+        let mut enemy = Self::from_boxed(name, cooldown, registry.build(&spec)?);
+        enemy.spec = Some(spec);
+
+        Ok(enemy)
+    }
+
+    /// Sets the enemy's aggression level. `MAX_AGGRESSION`, which every enemy starts at, keeps the
+    /// enemy's usual cooldowns and behavior. Lower levels act less often and are slower to wake up,
+    /// up to twice as slow, tone down whatever their behavior does, and level 0 never wakes up at
+    /// all
+    pub fn with_level(mut self, level: u8) -> Self {
+        self.level = level.min(MAX_AGGRESSION);
+        self.behavior.set_aggression(self.aggression());
+        let scale = (2 * MAX_AGGRESSION - self.level) as f64 / MAX_AGGRESSION as f64;
+
+        self.scale_cooldown(scale)
+    }
+
+    /// The enemy's aggression level as a fraction of `MAX_AGGRESSION`
+    fn aggression(&self) -> f64 {
+        self.level as f64 / MAX_AGGRESSION as f64
+    }
+
+    /// Sets where the enemy goes when one of its attacks is blocked
+    pub fn with_retreat(mut self, retreat: Retreat) -> Self {
+        self.retreat = retreat;
+        self
+    }
+
+    /// Speeds up (or slows down) the enemy by scaling its range of cooldown times
+    pub fn scale_cooldown(mut self, scale: f64) -> Self {
+        let scaled = |ticks: u64| ((ticks as f64 * scale) as u64).max(1);
+        let start = scaled(self.cooldown.start);
+
+        self.cooldown = start..scaled(self.cooldown.end).max(start + 1);
+        self
+    }
+
     #[cfg(test)]
     /// Creates a testable default enemy type with the generic pathfinding behavior
     pub fn default_test_enemy() -> Self {
         use impls::generic::StraightPathBehavior;
 
         Self {
-            name: "Test Entity",
+            name: "Test Entity".to_string(),
             state: State::Dormant,
             cooldown: 1..5,
             behavior: Box::new(StraightPathBehavior::default()),
+            level: MAX_AGGRESSION,
+            spec: None,
+            retreat: Retreat::default(),
         }
     }
 
     /// Returns the enemy's name
-    pub fn get_name(&self) -> &'static str {
-        self.name
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the enemy is awake or not
+    pub fn get_state(&self) -> State {
+        self.state
     }
 
     /// When it's an enemies turn, goes through all of the logic and game mutation given that
@@ -69,19 +149,22 @@ impl Freak {
     pub fn tick<RNG: Rng>(&mut self, id: EnemyId, curr_game: &mut GameState, rng: &mut RNG) {
         match self.state {
             State::Dormant => {
-                // Just wake up
+                // Just wake up, if we're aggressive enough to
 
-                self.state = State::Moving
+                if rng.gen_range(0..MAX_AGGRESSION) < self.level {
+                    self.state = State::Moving;
+                    curr_game.emit(GameEvent::EnemyWokeUp { enemy: id });
+                }
             }
             State::Moving => {
                 // Begin performing actions
 
-                let actions = self.behavior.tick(curr_game, id);
+                let actions = self.behavior.tick(curr_game, id, rng);
 
                 for action in actions {
                     match action {
                         Action::Move(move_to) => curr_game.move_enemy(id, move_to, rng),
-                        Action::Attack => curr_game.attack(id, rng),
+                        Action::Attack => curr_game.attack(id, self.retreat, rng),
                         Action::Special(side_effect) => side_effect.do_something(curr_game),
                         Action::Nothing => {}
                     }
@@ -90,17 +173,126 @@ impl Freak {
         }
     }
 
+    /// Anything that should wake an awake enemy up before its next turn
+    pub fn wake_on(&self, id: EnemyId, curr_game: &GameState) -> Vec<Trigger> {
+        match self.state {
+            State::Dormant => vec![],
+            State::Moving => self.behavior.wake_on(curr_game, id),
+        }
+    }
+
+    /// Creates a fresh copy of this enemy, awake and ready to move, if it was built from a spec
+    pub fn duplicate(&self, registry: &BehaviorRegistry) -> Option<Self> {
+        let spec = self.spec.clone()?;
+        let mut copy =
+            Self::from_spec(self.name.clone(), self.cooldown.clone(), spec, registry).ok()?;
+        copy.level = self.level;
+        copy.behavior.set_aggression(copy.aggression());
+        copy.retreat = self.retreat;
+        copy.state = State::Moving;
+
+        Some(copy)
+    }
+
+    /// Snapshots the enemy along with whatever its behavior remembers between turns
+    pub fn save(&self) -> SavedFreak {
+        SavedFreak {
+            name: self.name.clone(),
+            cooldown: self.cooldown.clone(),
+            level: self.level,
+            spec: self.spec.clone(),
+            retreat: self.retreat,
+            state: self.state,
+            behavior: self.behavior.save(),
+        }
+    }
+
+    /// Rebuilds an enemy from a snapshot produced by [`Freak::save`]
+    pub fn restore(saved: SavedFreak, registry: &BehaviorRegistry) -> Result<Self, String> {
+        let spec = saved
+            .spec
+            .ok_or_else(|| format!("`{}` wasn't built from a spec", saved.name))?;
+
+        let mut enemy = Self::from_spec(saved.name, saved.cooldown, spec, registry)?;
+        enemy.level = saved.level;
+        enemy.behavior.set_aggression(enemy.aggression());
+        enemy.retreat = saved.retreat;
+        enemy.state = saved.state;
+        enemy
+            .behavior
+            .load(saved.behavior)
+            .map_err(|err| err.to_string())?;
+
+        Ok(enemy)
+    }
+
     /// Given the enemies range of cooldown times, returns one of them randomly
     pub fn gen_cooldown<RNG: Rng>(&self, rng: &mut RNG) -> u64 {
         rng.gen_range(self.cooldown.clone())
     }
 }
 
+/// A serializable snapshot of an enemy
+#[derive(Serialize, Deserialize)]
+pub struct SavedFreak {
+    /// The enemy's name
+    name: String,
+    /// The enemy's range of cooldown times
+    cooldown: Range<u64>,
+    /// The enemy's aggression level
+    level: u8,
+    /// The spec the enemy's behavior was built from
+    spec: Option<serde_json::Value>,
+    /// Where the enemy goes when its attack is blocked
+    #[serde(default)]
+    retreat: Retreat,
+    /// The enemy's state
+    state: State,
+    /// The behavior's internal state
+    behavior: serde_json::Value,
+}
+
 /// Whether the enemy is activated or not
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum State {
     /// Enemy is currently asleep
     Dormant,
     /// Enemy is on the move
     Moving,
 }
+
+/// Where an enemy goes when the player shuts the door on its attack
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Retreat {
+    /// Back to a random spawn point
+    #[default]
+    Spawn,
+    /// This many rooms back the way it came, away from the office
+    Back(usize),
+    /// Nowhere, it waits at the door for this many ticks before acting again
+    Wait(u64),
+    /// Over to the other entrance, to try the other door
+    OppositeEntrance,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Freak, MAX_AGGRESSION};
+
+    #[test]
+    fn levels_only_slow_enemies_down_from_the_default() {
+        let default = Freak::default_test_enemy().scale_cooldown(100.0);
+        let cooldown = default.cooldown.clone();
+
+        let max = Freak::default_test_enemy()
+            .scale_cooldown(100.0)
+            .with_level(MAX_AGGRESSION);
+        assert_eq!(max.cooldown, cooldown);
+
+        let half = Freak::default_test_enemy()
+            .scale_cooldown(100.0)
+            .with_level(MAX_AGGRESSION / 2);
+        assert_eq!(half.cooldown, 150..750);
+    }
+}
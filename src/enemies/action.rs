@@ -1,13 +1,60 @@
 //! Enemy behavior trait definition
 
-use crate::{GameState, RoomId};
+use rand::RngCore;
+
+use crate::{schedule::Trigger, GameState, RoomId};
 
 use super::EnemyId;
 
 /// The behavior of an enemy that happens each tick of the game
 pub trait EnemyBehavior {
-    /// Given the current game's context, performs an arbitrary amount of actions
-    fn tick(&mut self, curr_state: &GameState, id: EnemyId) -> Vec<Action>;
+    /// Given the current game's context, performs an arbitrary amount of actions. Any randomness
+    /// must be drawn from `rng` so that seeded games stay reproducible
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action>;
+
+    /// Asked after every turn for anything that should wake the enemy up for another turn before
+    /// its cooldown is over. Most behaviors are happy to wait and can leave this as is
+    fn wake_on(&self, _curr_state: &GameState, _id: EnemyId) -> Vec<Trigger> {
+        vec![]
+    }
+
+    /// Tunes the behavior to an aggression level, from 0 for the most timid up to 1 for how it
+    /// usually acts. Behaviors without anything to tune can leave this as is, but ones wrapping
+    /// other behaviors should pass it on
+    fn set_aggression(&mut self, _aggression: f64) {}
+
+    /// Serializes whatever the behavior remembers between turns so it survives a save. Stateless
+    /// behaviors can leave this as is
+    fn save(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Restores the internal state produced by `save`
+    fn load(&mut self, _state: serde_json::Value) -> serde_json::Result<()> {
+        Ok(())
+    }
+}
+
+impl EnemyBehavior for Box<dyn EnemyBehavior> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        (**self).tick(curr_state, id, rng)
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        (**self).wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        (**self).set_aggression(aggression)
+    }
+
+    fn save(&self) -> serde_json::Value {
+        (**self).save()
+    }
+
+    fn load(&mut self, state: serde_json::Value) -> serde_json::Result<()> {
+        (**self).load(state)
+    }
 }
 
 /// All different actions an enemy can do in a turn
@@ -0,0 +1,102 @@
+//! Built-in side effects enemies can cause through `Action::Special`
+
+use crate::{enemies::EnemyId, map::RoomId, Door, GameState};
+
+use super::action::SideEffect;
+
+/// Knocks out a room's camera for a while
+pub struct DisableCamera {
+    /// The room whose camera goes dark
+    pub room: RoomId,
+    /// How many ticks until the camera comes back
+    pub ticks: u64,
+}
+
+impl SideEffect for DisableCamera {
+    fn do_something(&self, game: &mut GameState) {
+        game.disable_camera_for(self.room, self.ticks)
+    }
+}
+
+/// Drains a chunk of the player's power
+pub struct DrainPower(pub i32);
+
+impl SideEffect for DrainPower {
+    fn do_something(&self, game: &mut GameState) {
+        game.drain_power(self.0)
+    }
+}
+
+/// Jams a door open or shut so the player can't toggle it for a while
+pub struct JamDoor {
+    /// The door to jam
+    pub door: Door,
+    /// Whether the door is jammed shut or open
+    pub closed: bool,
+    /// How many ticks until the door comes unstuck
+    pub ticks: u64,
+}
+
+impl SideEffect for JamDoor {
+    fn do_something(&self, game: &mut GameState) {
+        game.jam_door(self.door, self.closed, self.ticks)
+    }
+}
+
+/// Bangs on a closed door, draining power every tick until the banging stops or the door opens
+pub struct BangOnDoor {
+    /// The door to bang on
+    pub door: Door,
+    /// How much power is drained each tick
+    pub drain: i32,
+    /// How many ticks the banging goes on for
+    pub ticks: u64,
+}
+
+impl SideEffect for BangOnDoor {
+    fn do_something(&self, game: &mut GameState) {
+        game.bang_on_door(self.door, self.drain, self.ticks)
+    }
+}
+
+/// Fills a room's camera with static
+pub struct CameraStatic(pub RoomId);
+
+impl SideEffect for CameraStatic {
+    fn do_something(&self, game: &mut GameState) {
+        game.show_static(self.0)
+    }
+}
+
+/// Puts off an enemy's next turn until a given number of ticks from now, rather than it rolling
+/// its usual cooldown
+pub struct NextTurnIn {
+    /// The enemy whose turn it is
+    pub enemy: EnemyId,
+    /// How many ticks until its next turn
+    pub ticks: u64,
+}
+
+impl SideEffect for NextTurnIn {
+    fn do_something(&self, game: &mut GameState) {
+        game.schedule_turn(self.enemy, self.ticks)
+    }
+}
+
+/// Turns the clock back, making the night that much longer
+pub struct RewindTime(pub u64);
+
+impl SideEffect for RewindTime {
+    fn do_something(&self, game: &mut GameState) {
+        game.rewind(self.0)
+    }
+}
+
+/// Spawns a copy of an enemy at one of the spawn points
+pub struct SpawnCopy(pub EnemyId);
+
+impl SideEffect for SpawnCopy {
+    fn do_something(&self, game: &mut GameState) {
+        game.spawn_copy(self.0)
+    }
+}
@@ -1,5 +1,19 @@
 //! Implementations for enemy behaviors
 
+pub mod ambush;
+pub mod banger;
+pub mod combinators;
 pub mod double;
+pub mod drainer;
 pub mod generic;
+pub mod learning;
+pub mod pack;
 pub mod random;
+pub mod saboteur;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod shy;
+pub mod stalker;
+pub mod teleport;
+pub mod vent;
+pub mod walk;
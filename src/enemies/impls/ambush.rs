@@ -0,0 +1,239 @@
+//! An enemy implementation that lies in wait outside the office, pouncing the moment the player
+//! opens the door
+
+use std::ops::Range;
+
+use rand::{Rng, RngCore};
+use serde_json::{json, Value};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        effects::NextTurnIn,
+        EnemyId,
+    },
+    schedule::Trigger,
+    GameState,
+};
+
+/// Gets to the office like its inner behavior, then lurks at the entrance for a random number of
+/// ticks. If the door is open when it arrives, or is opened while it lurks, it
+/// attacks right away rather than waiting for its next turn. If the player keeps the door shut the
+/// whole time, it attacks anyway once the wait is over and gets sent away like any blocked attacker
+pub struct AmbushBehavior<BEHAVIOR: EnemyBehavior> {
+    /// How the enemy gets to the office
+    inner_behavior: BEHAVIOR,
+    /// The range of ticks to lurk at the entrance for
+    lurk: Range<u64>,
+    /// The tick the current ambush runs out on, if we're lurking
+    lurking_until: Option<u64>,
+}
+
+impl<BEHAVIOR: EnemyBehavior> AmbushBehavior<BEHAVIOR> {
+    /// Creates an ambusher that lurks for somewhere in the given range of ticks
+    pub fn new(inner_behavior: BEHAVIOR, lurk: Range<u64>) -> Self {
+        Self {
+            inner_behavior,
+            lurk,
+            lurking_until: None,
+        }
+    }
+
+    /// Is the door in front of us open?
+    fn door_open(curr_state: &GameState, id: EnemyId) -> bool {
+        curr_state
+            .map
+            .get_enemy_room(id)
+            .and_then(|room| curr_state.entrance_door(room))
+            .is_some_and(|door| !curr_state.door_closed(door))
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for AmbushBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let mut actions = self.inner_behavior.tick(curr_state, id, rng);
+        if !actions.contains(&Action::Attack) {
+            self.lurking_until = None;
+
+            // Arriving at an open door, pounce next tick instead of after a whole cooldown
+            let arriving_at_open_door = actions.iter().any(|action| {
+                matches!(action, Action::Move(room) if curr_state
+                    .entrance_door(*room)
+                    .is_some_and(|door| !curr_state.door_closed(door)))
+            });
+            if arriving_at_open_door {
+                actions.push(Action::Special(Box::new(NextTurnIn {
+                    enemy: id,
+                    ticks: 1,
+                })));
+            }
+
+            return actions;
+        }
+
+        let now = curr_state.elapsed();
+        let until = match self.lurking_until {
+            _ if Self::door_open(curr_state, id) => None,
+            None => {
+                let lurk = if self.lurk.is_empty() {
+                    self.lurk.start
+                } else {
+                    rng.gen_range(self.lurk.clone())
+                };
+                Some(now + lurk)
+            }
+            Some(until) if until > now => Some(until),
+            Some(_) => None,
+        };
+        self.lurking_until = until;
+
+        match until {
+            // Lurk until the door opens or the wait is over, whichever comes first
+            Some(until) => vec![Action::Special(Box::new(NextTurnIn {
+                enemy: id,
+                ticks: until - now,
+            }))],
+            None => actions,
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        let door = curr_state
+            .map
+            .get_enemy_room(id)
+            .and_then(|room| curr_state.entrance_door(room));
+
+        match door {
+            Some(door) if self.lurking_until.is_some() => vec![Trigger::DoorOpened(door)],
+            _ => self.inner_behavior.wake_on(curr_state, id),
+        }
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "lurking_until": self.lurking_until,
+            "inner": self.inner_behavior.save(),
+        })
+    }
+
+    fn load(&mut self, mut state: Value) -> serde_json::Result<()> {
+        self.lurking_until = serde_json::from_value(state["lurking_until"].take())?;
+        self.inner_behavior.load(state["inner"].take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{
+        enemies::{
+            impls::{combinators::ChanceBehavior, generic::StraightPathBehavior},
+            EnemyId, Freak,
+        },
+        Door, GameState,
+    };
+
+    use super::AmbushBehavior;
+
+    #[test]
+    fn ambusher_pounces_when_the_door_opens() {
+        let (mut game, [], mut rng) = GameState::test_night(0xA3B1);
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let id = enemies.insert(Freak::new(
+            "ambusher",
+            1000..1001,
+            AmbushBehavior::new(StraightPathBehavior::default(), 5000..5001),
+        ));
+        game.map.register_enemy(id, game.office.left);
+        game.toggle_door(Door::Left);
+
+        // Once to wake up, once to start lurking
+        game.take_turn(id, &mut enemies[id], &mut rng);
+        game.take_turn(id, &mut enemies[id], &mut rng);
+
+        for _ in 0..10 {
+            game.tick(&mut enemies, &mut rng);
+        }
+        assert_eq!(game.map.get_enemy_room(id), Some(game.office.left));
+
+        game.toggle_door(Door::Left);
+        game.tick(&mut enemies, &mut rng);
+        assert!(game.dead);
+    }
+
+    #[test]
+    fn wrapped_ambusher_still_pounces() {
+        let (mut game, [], mut rng) = GameState::test_night(0xA3B2);
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let behavior = ChanceBehavior::new(
+            1.0,
+            AmbushBehavior::new(StraightPathBehavior::default(), 5000..5001),
+        );
+        let id = enemies.insert(Freak::new("ambusher", 1000..1001, behavior));
+        game.map.register_enemy(id, game.office.left);
+        game.toggle_door(Door::Left);
+
+        // Once to wake up, once to start lurking
+        game.take_turn(id, &mut enemies[id], &mut rng);
+        game.take_turn(id, &mut enemies[id], &mut rng);
+
+        game.toggle_door(Door::Left);
+        game.tick(&mut enemies, &mut rng);
+        assert!(game.dead);
+    }
+
+    #[test]
+    fn ambusher_gives_up_lurking_on_time() {
+        let (mut game, [], mut rng) = GameState::test_night(0xA3B3);
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let id = enemies.insert(Freak::new(
+            "ambusher",
+            1000..1001,
+            AmbushBehavior::new(StraightPathBehavior::default(), 5..6),
+        ));
+        game.map.register_enemy(id, game.office.left);
+        game.toggle_door(Door::Left);
+
+        game.take_turn(id, &mut enemies[id], &mut rng);
+        game.take_turn(id, &mut enemies[id], &mut rng);
+        for _ in 0..5 {
+            game.tick(&mut enemies, &mut rng);
+        }
+
+        // Shut out, so it's been sent back to a spawn point well before its cooldown was up
+        let room = game.map.get_enemy_room(id).unwrap();
+        assert!(game.spawn_points.contains(&room));
+    }
+
+    #[test]
+    fn ambusher_pounces_through_a_door_left_open() {
+        let (mut game, [], mut rng) = GameState::test_night(0xA3B4);
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let id = enemies.insert(Freak::new(
+            "ambusher",
+            1000..1001,
+            AmbushBehavior::new(StraightPathBehavior::default(), 5000..5001),
+        ));
+        let office = &game.office;
+        let hallway = game.map.0[office.left]
+            .connections()
+            .iter()
+            .copied()
+            .find(|room| *room != office.root)
+            .unwrap();
+        game.map.register_enemy(id, hallway);
+
+        // Once to wake up, once to get to the door
+        game.take_turn(id, &mut enemies[id], &mut rng);
+        game.take_turn(id, &mut enemies[id], &mut rng);
+        assert_eq!(game.map.get_enemy_room(id), Some(game.office.left));
+
+        game.tick(&mut enemies, &mut rng);
+        assert!(game.dead);
+    }
+}
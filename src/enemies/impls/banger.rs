@@ -0,0 +1,135 @@
+//! An enemy implementation that, when shut out, bangs on the door instead of giving up straight
+//! away
+
+use rand::RngCore;
+use serde_json::{json, Value};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        effects::BangOnDoor,
+        EnemyId,
+    },
+    schedule::Trigger,
+    GameState,
+};
+
+/// Gets to the office like its inner behavior, but when it finds the door shut it stays and bangs
+/// on it, draining power every tick. Only once the banging is over and the door is still shut
+/// does it give up and attack anyway, getting sent away like any blocked attacker
+pub struct BangerBehavior<BEHAVIOR: EnemyBehavior> {
+    /// How the enemy gets to the office
+    inner_behavior: BEHAVIOR,
+    /// How much power is drained each tick spent banging
+    drain: i32,
+    /// How aggressive the banger is, scaling down how much its banging drains
+    aggression: f64,
+    /// How many ticks the enemy bangs on the door for before giving up
+    ticks: u64,
+    /// Whether the enemy has already had its go at banging on this door
+    banged: bool,
+}
+
+impl<BEHAVIOR: EnemyBehavior> BangerBehavior<BEHAVIOR> {
+    /// Creates a banger that drains `drain` power a tick for `ticks` ticks while shut out
+    pub fn new(inner_behavior: BEHAVIOR, drain: i32, ticks: u64) -> Self {
+        Self {
+            inner_behavior,
+            drain,
+            aggression: 1.0,
+            ticks,
+            banged: false,
+        }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for BangerBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let actions = self.inner_behavior.tick(curr_state, id, rng);
+        let door = curr_state
+            .map
+            .get_enemy_room(id)
+            .and_then(|room| curr_state.entrance_door(room));
+
+        match door {
+            Some(door) if actions.contains(&Action::Attack) && curr_state.door_closed(door) => {
+                if curr_state.is_banging(door) {
+                    vec![Action::Nothing]
+                } else if self.banged {
+                    self.banged = false;
+                    actions
+                } else {
+                    self.banged = true;
+                    vec![Action::Special(Box::new(BangOnDoor {
+                        door,
+                        drain: (self.drain as f64 * self.aggression) as i32,
+                        ticks: self.ticks,
+                    }))]
+                }
+            }
+            _ => {
+                self.banged = false;
+                actions
+            }
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.aggression = aggression.clamp(0.0, 1.0);
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "banged": self.banged,
+            "inner": self.inner_behavior.save(),
+        })
+    }
+
+    fn load(&mut self, mut state: Value) -> serde_json::Result<()> {
+        self.banged = serde_json::from_value(state["banged"].take())?;
+        self.inner_behavior.load(state["inner"].take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{
+        enemies::{impls::generic::StraightPathBehavior, EnemyId, Freak},
+        Door, GameState,
+    };
+
+    use super::BangerBehavior;
+
+    #[test]
+    fn banger_drains_power_at_a_closed_door_then_gives_up() {
+        let (mut game, [], mut rng) = GameState::test_night(0xBA9);
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let behavior = BangerBehavior::new(StraightPathBehavior::default(), 100, 3);
+        let id = enemies.insert(Freak::new("banger", 1..2, behavior));
+        game.map.register_enemy(id, game.office.left);
+        game.toggle_door(Door::Left);
+
+        // Wake up, then start banging
+        enemies[id].tick(id, &mut game, &mut rng);
+        enemies[id].tick(id, &mut game, &mut rng);
+        assert!(game.is_banging(Door::Left));
+
+        let power = game.power();
+        game.tick(&mut enemies, &mut rng);
+        assert!(power - game.power() > 100);
+
+        game.tick(&mut enemies, &mut rng);
+        assert_eq!(game.map.get_enemy_room(id), Some(game.office.left));
+
+        game.tick(&mut enemies, &mut rng);
+        assert!(!game.is_banging(Door::Left));
+        assert_ne!(game.map.get_enemy_room(id), Some(game.office.left));
+    }
+}
@@ -0,0 +1,424 @@
+//! Behaviors that wrap other behaviors, so new enemy personalities can be built by composing
+//! existing ones
+
+use rand::{Rng, RngCore};
+use serde_json::{json, Value};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    schedule::Trigger,
+    GameState,
+};
+
+/// Only acts on a turn with the given chance, otherwise does nothing
+pub struct ChanceBehavior<BEHAVIOR: EnemyBehavior> {
+    /// The chance of acting on any given turn
+    chance: f64,
+    /// What to do when we do act
+    inner_behavior: BEHAVIOR,
+}
+
+impl<BEHAVIOR: EnemyBehavior> ChanceBehavior<BEHAVIOR> {
+    /// Creates a behavior that acts with the given chance each turn
+    pub fn new(chance: f64, inner_behavior: BEHAVIOR) -> Self {
+        Self {
+            chance: chance.clamp(0.0, 1.0),
+            inner_behavior,
+        }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for ChanceBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        if rng.gen_bool(self.chance) {
+            self.inner_behavior.tick(curr_state, id, rng)
+        } else {
+            vec![Action::Nothing]
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
+}
+
+/// Performs every behavior's actions one after another in the same turn. Each behavior sees the
+/// game as it was at the start of the turn, so the sequence stops at the first one that moves or
+/// attacks rather than letting the rest act on where the enemy used to be
+pub struct SequenceBehavior {
+    /// The behaviors to run, in order
+    behaviors: Vec<Box<dyn EnemyBehavior>>,
+}
+
+impl SequenceBehavior {
+    /// Creates a sequence of behaviors
+    pub fn new(behaviors: Vec<Box<dyn EnemyBehavior>>) -> Self {
+        Self { behaviors }
+    }
+}
+
+impl EnemyBehavior for SequenceBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let mut actions = vec![];
+
+        for behavior in &mut self.behaviors {
+            let next = behavior.tick(curr_state, id, rng);
+            let done = next
+                .iter()
+                .any(|action| matches!(action, Action::Move(_) | Action::Attack));
+            actions.extend(next);
+
+            if done {
+                break;
+            }
+        }
+
+        actions
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.behaviors
+            .iter()
+            .flat_map(|behavior| behavior.wake_on(curr_state, id))
+            .collect()
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        for behavior in &mut self.behaviors {
+            behavior.set_aggression(aggression);
+        }
+    }
+
+    fn save(&self) -> Value {
+        self.behaviors
+            .iter()
+            .map(|behavior| behavior.save())
+            .collect()
+    }
+
+    fn load(&mut self, state: Value) -> serde_json::Result<()> {
+        let states: Vec<Value> = serde_json::from_value(state)?;
+        for (behavior, state) in self.behaviors.iter_mut().zip(states) {
+            behavior.load(state)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Only acts while the player's cameras are off
+pub struct WhenCamerasOffBehavior<BEHAVIOR: EnemyBehavior> {
+    /// What to do while nobody's looking
+    inner_behavior: BEHAVIOR,
+}
+
+impl<BEHAVIOR: EnemyBehavior> WhenCamerasOffBehavior<BEHAVIOR> {
+    /// Creates a behavior that only acts while the cameras are off
+    pub fn new(inner_behavior: BEHAVIOR) -> Self {
+        Self { inner_behavior }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for WhenCamerasOffBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        if curr_state.cameras_on() {
+            vec![Action::Nothing]
+        } else {
+            self.inner_behavior.tick(curr_state, id, rng)
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
+}
+
+/// Does nothing until the night reaches a given hour
+pub struct AfterHourBehavior<BEHAVIOR: EnemyBehavior> {
+    /// The hour (counting from 0 at 12 AM) to start acting on
+    hour: u64,
+    /// What to do once it's late enough
+    inner_behavior: BEHAVIOR,
+}
+
+impl<BEHAVIOR: EnemyBehavior> AfterHourBehavior<BEHAVIOR> {
+    /// Creates a behavior that only starts acting at the given hour
+    pub fn new(hour: u64, inner_behavior: BEHAVIOR) -> Self {
+        Self {
+            hour,
+            inner_behavior,
+        }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for AfterHourBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        if curr_state.hour() >= self.hour {
+            self.inner_behavior.tick(curr_state, id, rng)
+        } else {
+            vec![Action::Nothing]
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
+}
+
+/// Switches between two behaviors every turn, starting with the first
+pub struct AlternateBehavior<FIRST: EnemyBehavior, SECOND: EnemyBehavior> {
+    /// The behavior used on odd turns
+    first: FIRST,
+    /// The behavior used on even turns
+    second: SECOND,
+    /// Whether the second behavior is up next
+    second_next: bool,
+}
+
+impl<FIRST: EnemyBehavior, SECOND: EnemyBehavior> AlternateBehavior<FIRST, SECOND> {
+    /// Creates a behavior that alternates between `first` and `second`
+    pub fn new(first: FIRST, second: SECOND) -> Self {
+        Self {
+            first,
+            second,
+            second_next: false,
+        }
+    }
+}
+
+impl<FIRST: EnemyBehavior, SECOND: EnemyBehavior> EnemyBehavior
+    for AlternateBehavior<FIRST, SECOND>
+{
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let actions = if self.second_next {
+            self.second.tick(curr_state, id, rng)
+        } else {
+            self.first.tick(curr_state, id, rng)
+        };
+        self.second_next = !self.second_next;
+
+        actions
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        if self.second_next {
+            self.second.wake_on(curr_state, id)
+        } else {
+            self.first.wake_on(curr_state, id)
+        }
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.first.set_aggression(aggression);
+        self.second.set_aggression(aggression);
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "second_next": self.second_next,
+            "first": self.first.save(),
+            "second": self.second.save(),
+        })
+    }
+
+    fn load(&mut self, mut state: Value) -> serde_json::Result<()> {
+        self.second_next = serde_json::from_value(state["second_next"].take())?;
+        self.first.load(state["first"].take())?;
+        self.second.load(state["second"].take())
+    }
+}
+
+/// Sits out a number of turns after each turn the inner behavior actually does something
+pub struct CooldownBehavior<BEHAVIOR: EnemyBehavior> {
+    /// How many turns to sit out after acting
+    turns: u64,
+    /// How many turns are left to sit out
+    resting: u64,
+    /// What to do when not resting
+    inner_behavior: BEHAVIOR,
+}
+
+impl<BEHAVIOR: EnemyBehavior> CooldownBehavior<BEHAVIOR> {
+    /// Creates a behavior that rests for `turns` turns after acting
+    pub fn new(turns: u64, inner_behavior: BEHAVIOR) -> Self {
+        Self {
+            turns,
+            resting: 0,
+            inner_behavior,
+        }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for CooldownBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        if self.resting > 0 {
+            self.resting -= 1;
+            return vec![Action::Nothing];
+        }
+
+        let actions = self.inner_behavior.tick(curr_state, id, rng);
+        if actions.iter().any(|action| *action != Action::Nothing) {
+            self.resting = self.turns;
+        }
+
+        actions
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        // Waking up early would only cut the rest short
+        if self.resting > 0 {
+            vec![]
+        } else {
+            self.inner_behavior.wake_on(curr_state, id)
+        }
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "resting": self.resting,
+            "inner": self.inner_behavior.save(),
+        })
+    }
+
+    fn load(&mut self, mut state: Value) -> serde_json::Result<()> {
+        self.resting = serde_json::from_value(state["resting"].take())?;
+        self.inner_behavior.load(state["inner"].take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{
+        enemies::{
+            action::{Action, EnemyBehavior},
+            impls::generic::StraightPathBehavior,
+            EnemyId, Freak,
+        },
+        GameState,
+    };
+
+    use super::{
+        AfterHourBehavior, AlternateBehavior, ChanceBehavior, CooldownBehavior, SequenceBehavior,
+        WhenCamerasOffBehavior,
+    };
+
+    /// A behavior that always attacks, for checking when it gets to act
+    struct AlwaysAttack;
+
+    impl EnemyBehavior for AlwaysAttack {
+        fn tick(&mut self, _: &GameState, _: EnemyId, _: &mut dyn rand::RngCore) -> Vec<Action> {
+            vec![Action::Attack]
+        }
+    }
+
+    #[test]
+    fn combinators_gate_their_inner_behavior() {
+        let (game, [id], mut rng) = GameState::test_night(0xC0DE);
+        let mut game = game.with_enemies(&[id], &mut rng);
+
+        let mut cooldown = CooldownBehavior::new(2, AlwaysAttack);
+        let turns: Vec<_> = (0..6)
+            .map(|_| cooldown.tick(&game, id, &mut rng).contains(&Action::Attack))
+            .collect();
+        assert_eq!(turns, [true, false, false, true, false, false]);
+
+        let mut alternate = AlternateBehavior::new(AlwaysAttack, StraightPathBehavior::default());
+        assert!(alternate
+            .tick(&game, id, &mut rng)
+            .contains(&Action::Attack));
+        assert!(!alternate
+            .tick(&game, id, &mut rng)
+            .contains(&Action::Attack));
+
+        let mut shy = WhenCamerasOffBehavior::new(AlwaysAttack);
+        game.toggle_cameras();
+        assert!(shy.tick(&game, id, &mut rng) == [Action::Nothing]);
+    }
+
+    #[test]
+    fn chance_and_after_hour_hold_their_inner_behavior_back() {
+        let (game, [id], mut rng) = GameState::test_night(0xC0DF);
+        let mut game = game.with_enemies(&[id], &mut rng);
+
+        let mut never = ChanceBehavior::new(0.0, AlwaysAttack);
+        let mut always = ChanceBehavior::new(1.0, AlwaysAttack);
+        assert!(never.tick(&game, id, &mut rng) == [Action::Nothing]);
+        assert!(always.tick(&game, id, &mut rng) == [Action::Attack]);
+
+        let mut late = AfterHourBehavior::new(1, AlwaysAttack);
+        assert!(late.tick(&game, id, &mut rng) == [Action::Nothing]);
+
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        while game.hour() < 1 {
+            game.tick(&mut enemies, &mut rng);
+        }
+        assert!(late.tick(&game, id, &mut rng) == [Action::Attack]);
+    }
+
+    #[test]
+    fn sequences_stop_at_the_first_move() {
+        let (game, [id], mut rng) = GameState::test_night(0xC0E0);
+        let game = game.with_enemies(&[id], &mut rng);
+
+        let mut sequence = SequenceBehavior::new(vec![
+            Box::new(StraightPathBehavior::default()),
+            Box::new(StraightPathBehavior::default()),
+        ]);
+        let actions = sequence.tick(&game, id, &mut rng);
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(actions[0], Action::Move(_)));
+
+        let mut sequence = SequenceBehavior::new(vec![
+            Box::new(ChanceBehavior::new(0.0, AlwaysAttack)),
+            Box::new(AlwaysAttack),
+            Box::new(AlwaysAttack),
+        ]);
+        let actions = sequence.tick(&game, id, &mut rng);
+        assert!(actions[..] == [Action::Nothing, Action::Attack]);
+    }
+}
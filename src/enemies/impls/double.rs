@@ -1,10 +1,13 @@
 //! A behavior implementation that quickly performs two actions each time
 
+use rand::RngCore;
+
 use crate::{
     enemies::{
         action::{Action, EnemyBehavior},
         EnemyId,
     },
+    schedule::Trigger,
     GameState,
 };
 
@@ -23,12 +26,12 @@ impl<BEHAVIOR: EnemyBehavior> DoubleBehavior<BEHAVIOR> {
 }
 
 impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for DoubleBehavior<BEHAVIOR> {
-    fn tick(&mut self, curr_state: &GameState, id: EnemyId) -> Vec<Action> {
-        let mut action_1 = self.inner_behavior.tick(curr_state, id);
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let mut action_1 = self.inner_behavior.tick(curr_state, id, rng);
         if action_1.contains(&Action::Attack) {
             action_1
         } else {
-            let action_2 = self.inner_behavior.tick(curr_state, id);
+            let action_2 = self.inner_behavior.tick(curr_state, id, rng);
             if action_2.contains(&Action::Attack) {
                 action_1
             } else {
@@ -37,4 +40,20 @@ impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for DoubleBehavior<BEHAVIOR> {
             }
         }
     }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> serde_json::Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: serde_json::Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
 }
@@ -0,0 +1,142 @@
+//! An enemy implementation that heads for the office but, when the door is shut, sits outside it
+//! and eats away at the power until there's none left to keep it shut
+
+use rand::RngCore;
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        effects::DrainPower,
+        EnemyId,
+    },
+    schedule::Trigger,
+    GameState,
+};
+
+/// Gets to the office like its inner behavior, attacking through an open door. When the door is
+/// shut, it drains power each turn instead and only attacks once the power is gone
+pub struct DrainerBehavior<BEHAVIOR: EnemyBehavior> {
+    /// How the enemy gets to the office
+    inner_behavior: BEHAVIOR,
+    /// How much power is drained each turn spent at the door
+    drain: i32,
+    /// How aggressive the drainer is, scaling down how much it drains
+    aggression: f64,
+}
+
+impl<BEHAVIOR: EnemyBehavior> DrainerBehavior<BEHAVIOR> {
+    /// Creates a drainer that takes `drain` power each turn it spends at the door
+    pub fn new(inner_behavior: BEHAVIOR, drain: i32) -> Self {
+        Self {
+            inner_behavior,
+            drain,
+            aggression: 1.0,
+        }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for DrainerBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let actions = self.inner_behavior.tick(curr_state, id, rng);
+        let shut_out = curr_state
+            .map
+            .get_enemy_room(id)
+            .and_then(|room| curr_state.entrance_door(room))
+            .is_some_and(|door| curr_state.door_closed(door));
+
+        if actions.contains(&Action::Attack) && shut_out && curr_state.power() > 0 {
+            let drain = (self.drain as f64 * self.aggression) as i32;
+            vec![Action::Special(Box::new(DrainPower(drain)))]
+        } else {
+            actions
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.aggression = aggression.clamp(0.0, 1.0);
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> serde_json::Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: serde_json::Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{
+        enemies::{impls::generic::StraightPathBehavior, EnemyId, Freak, MAX_AGGRESSION},
+        Door, GameState,
+    };
+
+    use super::DrainerBehavior;
+
+    #[test]
+    fn drainer_eats_power_while_shut_out() {
+        let (mut game, [], mut rng) = GameState::test_night(0xD2A1);
+        let mut enemy_map: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let behavior = DrainerBehavior::new(StraightPathBehavior::default(), 1000);
+        let gopher = enemy_map.insert(Freak::new("gopher", 1..2, behavior));
+        game.map.register_enemy(gopher, game.office.left);
+        game.toggle_door(Door::Left);
+        let power = game.power();
+
+        // Once to wake up, once to act
+        enemy_map[gopher].tick(gopher, &mut game, &mut rng);
+        enemy_map[gopher].tick(gopher, &mut game, &mut rng);
+
+        assert_eq!(game.power(), power - 1000);
+        assert_eq!(game.map.get_enemy_room(gopher), Some(game.office.left));
+
+        // With the power gone, it finally goes in
+        game.drain_power(power);
+        enemy_map[gopher].tick(gopher, &mut game, &mut rng);
+        assert_eq!(game.map.get_enemy_room(gopher), Some(game.office.root));
+    }
+
+    #[test]
+    fn timid_drainers_drain_less() {
+        let (mut game, [], mut rng) = GameState::test_night(0xD2A3);
+        let mut enemy_map: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let behavior = DrainerBehavior::new(StraightPathBehavior::default(), 1000);
+        let gopher = Freak::new("gopher", 1..2, behavior).with_level(MAX_AGGRESSION / 2);
+        let gopher = enemy_map.insert(gopher);
+        game.map.register_enemy(gopher, game.office.left);
+        game.toggle_door(Door::Left);
+        let power = game.power();
+
+        // Keep at it until it's woken up and had its turn at the door
+        while game.power() == power {
+            enemy_map[gopher].tick(gopher, &mut game, &mut rng);
+        }
+
+        assert_eq!(game.power(), power - 500);
+    }
+
+    #[test]
+    fn drainer_walks_straight_in_through_an_open_door() {
+        let (mut game, [], mut rng) = GameState::test_night(0xD2A2);
+        let mut enemy_map: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let behavior = DrainerBehavior::new(StraightPathBehavior::default(), 1000);
+        let gopher = enemy_map.insert(Freak::new("gopher", 1..2, behavior));
+        game.map.register_enemy(gopher, game.office.right);
+        let power = game.power();
+
+        // Once to wake up, once to act
+        enemy_map[gopher].tick(gopher, &mut game, &mut rng);
+        enemy_map[gopher].tick(gopher, &mut game, &mut rng);
+
+        assert_eq!(game.power(), power);
+        assert_eq!(game.map.get_enemy_room(gopher), Some(game.office.root));
+    }
+}
@@ -1,5 +1,8 @@
 //! A generic enemy implementation that simply goes straight to the player as fast as possible
 
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
 use crate::{
     enemies::{
         action::{Action, EnemyBehavior},
@@ -10,14 +13,19 @@ use crate::{
 
 /// The most generic enemy behavior possible, attempt to advance towards the player and if they're
 /// only 1 room away attempt to attack
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct StraightPathBehavior {
     /// The ideal
     ideal_path: Option<Vec<RoomId>>,
 }
 
 impl EnemyBehavior for StraightPathBehavior {
-    fn tick(&mut self, curr_state: &crate::GameState, id: EnemyId) -> Vec<Action> {
+    fn tick(
+        &mut self,
+        curr_state: &crate::GameState,
+        id: EnemyId,
+        _rng: &mut dyn RngCore,
+    ) -> Vec<Action> {
         if self.ideal_path.is_none() {
             let enemy_room = curr_state.map.get_enemy_room(id);
             if enemy_room.is_none() {
@@ -56,4 +64,13 @@ impl EnemyBehavior for StraightPathBehavior {
             vec![Action::Nothing]
         }
     }
+
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("Failed to serialize")
+    }
+
+    fn load(&mut self, state: serde_json::Value) -> serde_json::Result<()> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
 }
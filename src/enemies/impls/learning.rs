@@ -0,0 +1,99 @@
+//! An enemy implementation that learns which door the player likes to keep shut and goes for the
+//! other one
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    Door, GameState,
+};
+
+/// Picks an entrance for each approach, favouring whichever door the player has kept closed the
+/// least (tonight and on earlier nights of the campaign), then heads there without cutting through
+/// the office and attacks
+#[derive(Default, Serialize, Deserialize)]
+pub struct LearningBehavior {
+    /// The door we're making for on this approach, if we've picked one yet
+    target: Option<Door>,
+}
+
+impl EnemyBehavior for LearningBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+        let office = &curr_state.office;
+
+        let target = *self.target.get_or_insert_with(|| {
+            // The less a door's been guarded, the likelier we are to try it
+            let left_guarded = curr_state.door_habits().share(Door::Left);
+            if rng.gen_bool(1.0 - left_guarded) {
+                Door::Left
+            } else {
+                Door::Right
+            }
+        });
+        let entrance = match target {
+            Door::Left => office.left,
+            Door::Right => office.right,
+        };
+
+        if room == entrance {
+            self.target = None;
+            return vec![Action::Attack];
+        }
+
+        let path = curr_state
+            .map
+            .generate_path_avoiding(room, entrance, &[office.root])
+            .or_else(|| curr_state.map.generate_path(room, office.root));
+        match path {
+            Some(path) if path[1] == office.root => {
+                self.target = None;
+                vec![Action::Attack]
+            }
+            Some(path) => vec![Action::Move(path[1])],
+            None => vec![Action::Nothing],
+        }
+    }
+
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("Failed to serialize")
+    }
+
+    fn load(&mut self, state: serde_json::Value) -> serde_json::Result<()> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        campaign::DoorHabits,
+        enemies::action::{Action, EnemyBehavior},
+        Door, GameState,
+    };
+
+    use super::LearningBehavior;
+
+    #[test]
+    fn learner_goes_for_the_door_left_open() {
+        let (game, [id], mut rng) = GameState::test_night(0x1EA2);
+        let game = game.with_enemies(&[id], &mut rng).with_habits(DoorHabits {
+            left: 1_000_000,
+            right: 0,
+        });
+
+        let mut learner = LearningBehavior::default();
+        assert!(matches!(
+            learner.tick(&game, id, &mut rng)[..],
+            [Action::Move(_)]
+        ));
+        assert_eq!(learner.target, Some(Door::Right));
+    }
+}
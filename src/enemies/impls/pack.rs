@@ -0,0 +1,238 @@
+//! An enemy implementation for packs of enemies that work together, splitting up between the
+//! office's entrances and striking at the same time
+
+use std::{cell::RefCell, rc::Rc};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        effects::NextTurnIn,
+        EnemyId,
+    },
+    GameState,
+};
+
+/// What a pack knows collectively, shared between all of its members
+#[derive(Default, Serialize, Deserialize)]
+pub struct Blackboard {
+    /// Every member that's started moving, in the order they joined
+    members: Vec<EnemyId>,
+    /// Members waiting in position at their entrance
+    ready: Vec<EnemyId>,
+    /// Whether the pack is mid-attack, so every ready member should strike on its turn
+    charging: bool,
+}
+
+/// A pack's blackboard, as held by each of its members
+pub type SharedBlackboard = Rc<RefCell<Blackboard>>;
+
+/// Members take turns being sent to the left and right entrances so the player has to watch both
+/// doors. Once in position they hold off until the whole pack is ready (or they've run out of
+/// patience), then the first to strike rallies every other ready member to strike the tick after
+pub struct PackBehavior {
+    /// What the whole pack knows
+    blackboard: SharedBlackboard,
+    /// How many turns to wait in position for the rest of the pack before attacking alone
+    patience: u64,
+    /// How many turns this member has waited in position so far
+    waited: u64,
+}
+
+impl PackBehavior {
+    /// Creates a member of the pack sharing the given blackboard
+    pub fn new(blackboard: SharedBlackboard, patience: u64) -> Self {
+        Self {
+            blackboard,
+            patience,
+            waited: 0,
+        }
+    }
+}
+
+impl EnemyBehavior for PackBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, _rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+        let mut board = self.blackboard.borrow_mut();
+        let office = &curr_state.office;
+
+        if !board.members.contains(&id) {
+            board.members.push(id);
+        }
+        let index = board.members.iter().position(|member| *member == id);
+        let entrance = if index.is_some_and(|index| index % 2 == 1) {
+            office.right
+        } else {
+            office.left
+        };
+
+        // Make for our own entrance without cutting through the office, or for whichever
+        // entrance is closest if ours can't be reached that way
+        let path = curr_state
+            .map
+            .generate_path_avoiding(room, entrance, &[office.root])
+            .or_else(|| {
+                let mut path = curr_state.map.generate_path(room, office.root)?;
+                path.pop();
+                Some(path)
+            });
+        let Some(path) = path.filter(|path| !path.is_empty()) else {
+            return vec![Action::Nothing];
+        };
+
+        if path.len() > 1 {
+            board.ready.retain(|member| *member != id);
+            self.waited = 0;
+            return vec![Action::Move(path[1])];
+        }
+
+        if !board.ready.contains(&id) {
+            board.ready.push(id);
+        }
+        self.waited += 1;
+
+        if board.charging || board.ready.len() >= board.members.len() || self.waited > self.patience
+        {
+            board.ready.retain(|member| *member != id);
+            let charging = !board.ready.is_empty();
+            self.waited = 0;
+
+            // Leading the charge, so bring the rest of the pack's turns forward to strike with us
+            let mut actions: Vec<Action> = if charging && !board.charging {
+                board
+                    .ready
+                    .iter()
+                    .map(|member| {
+                        Action::Special(Box::new(NextTurnIn {
+                            enemy: *member,
+                            ticks: 1,
+                        }))
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+            board.charging = charging;
+            actions.push(Action::Attack);
+
+            actions
+        } else {
+            vec![Action::Nothing]
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "waited": self.waited,
+            "blackboard": &*self.blackboard.borrow(),
+        })
+    }
+
+    fn load(&mut self, mut state: Value) -> serde_json::Result<()> {
+        self.waited = serde_json::from_value(state["waited"].take())?;
+        *self.blackboard.borrow_mut() = serde_json::from_value(state["blackboard"].take())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{
+        enemies::{action::Action, roster::BehaviorRegistry, EnemyId, Freak},
+        Door, GameState,
+    };
+
+    #[test]
+    fn pack_splits_up_and_attacks_together() {
+        let registry = BehaviorRegistry::default();
+        let spec = serde_json::json!({ "pack": { "name": "dogs", "patience": 10 } });
+        let mut first = registry.build(&spec).expect("Build pack member");
+        let mut second = registry.build(&spec).expect("Build pack member");
+
+        let (game, [a, b], mut rng) = GameState::test_night(0x9AC4);
+        let mut game = game.with_enemies(&[a, b], &mut rng);
+
+        assert!(matches!(
+            first.tick(&game, a, &mut rng)[..],
+            [Action::Move(_)]
+        ));
+        assert!(matches!(
+            second.tick(&game, b, &mut rng)[..],
+            [Action::Move(_)]
+        ));
+
+        // The first member takes the left door and the second the right one
+        for (enemy, entrance) in [(a, game.office.left), (b, game.office.right)] {
+            let room = game.map.get_enemy_room(enemy).unwrap();
+            game.map.move_enemy_out_of(room, enemy);
+            game.map.move_enemy_to(entrance, enemy);
+        }
+
+        assert!(first.tick(&game, a, &mut rng) == [Action::Nothing]);
+        assert!(matches!(
+            second.tick(&game, b, &mut rng)[..],
+            [Action::Special(_), Action::Attack]
+        ));
+        assert!(first.tick(&game, a, &mut rng) == [Action::Attack]);
+    }
+
+    #[test]
+    fn the_pack_strikes_within_a_tick_of_each_other() {
+        let registry = BehaviorRegistry::default();
+        let spec = serde_json::json!({ "pack": { "name": "dogs", "patience": 10 } });
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let a = enemies.insert(Freak::from_spec("a", 1000..1001, spec.clone(), &registry).unwrap());
+        let b = enemies.insert(Freak::from_spec("b", 1000..1001, spec, &registry).unwrap());
+
+        let (mut game, [], mut rng) = GameState::test_night(0x9AC5);
+        let office = game.office.clone();
+        for (id, entrance) in [(a, office.left), (b, office.right)] {
+            let hallway = game.map.0[entrance]
+                .connections()
+                .iter()
+                .copied()
+                .find(|room| *room != office.root && *room != office.left && *room != office.right)
+                .unwrap();
+            game.map.register_enemy(id, hallway);
+        }
+        game.toggle_door(Door::Left);
+        game.toggle_door(Door::Right);
+
+        // Both wake up and get into position, then the first waits there for the second
+        for id in [a, b, a, b, a] {
+            game.take_turn(id, &mut enemies[id], &mut rng);
+        }
+        assert_eq!(game.map.get_enemy_room(a), Some(office.left));
+        assert_eq!(game.map.get_enemy_room(b), Some(office.right));
+
+        // The second charges, calling the first in right behind it rather than a cooldown later
+        game.take_turn(b, &mut enemies[b], &mut rng);
+        assert_eq!(game.schedule.next_turn(a), Some(game.elapsed() + 1));
+    }
+
+    #[test]
+    fn copies_and_restored_members_stay_in_their_pack() {
+        let registry = BehaviorRegistry::default();
+        let spec = serde_json::json!({ "pack": { "name": "dogs" } });
+        let dog = Freak::from_spec("dog", 1..2, spec, &registry).expect("Build pack member");
+        let mut copy = dog.duplicate(&registry).expect("Copy pack member");
+
+        let (game, [id], mut rng) = GameState::test_night(0xC0B1);
+        let mut game = game.with_enemies(&[id], &mut rng);
+
+        copy.tick(id, &mut game, &mut rng);
+        assert_eq!(registry.pack("dogs").borrow().members, [id]);
+
+        let restored_registry = BehaviorRegistry::default();
+        Freak::restore(copy.save(), &restored_registry).expect("Restore pack member");
+        assert_eq!(restored_registry.pack("dogs").borrow().members, [id]);
+    }
+}
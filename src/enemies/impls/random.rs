@@ -1,5 +1,5 @@
 //! An enemy implementation that randomly decides where to go each round
-use rand::{seq::SliceRandom, Rng};
+use rand::{seq::SliceRandom, RngCore};
 
 use crate::{
     enemies::{
@@ -10,23 +10,14 @@ use crate::{
 };
 
 /// Every turn, the enemy chooses a random path from the current room it's in
-pub struct RandomBehavior<RNG: Rng> {
-    /// Rng used for choosing a room each time
-    rng: RNG,
-}
-
-impl<RNG: Rng> RandomBehavior<RNG> {
-    /// Creates a new behavior based on existing Rng
-    pub fn new(rng: RNG) -> Self {
-        Self { rng }
-    }
-}
+#[derive(Default)]
+pub struct RandomBehavior;
 
-impl<RNG: Rng> EnemyBehavior for RandomBehavior<RNG> {
-    fn tick(&mut self, curr_state: &GameState, id: EnemyId) -> Vec<Action> {
+impl EnemyBehavior for RandomBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
         if let Some(enemy_room) = curr_state.map.get_enemy_room(id) {
             let rooms = curr_state.map.0[enemy_room].connections();
-            let goto = rooms.choose(&mut self.rng).unwrap();
+            let goto = rooms.choose(rng).unwrap();
 
             if goto == &curr_state.office.root {
                 vec![Action::Attack]
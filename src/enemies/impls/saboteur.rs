@@ -0,0 +1,105 @@
+//! A behavior implementation that causes trouble on top of however it gets around
+
+use rand::{Rng, RngCore};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior, SideEffect},
+        effects::{DisableCamera, DrainPower, JamDoor, RewindTime, SpawnCopy},
+        EnemyId,
+    },
+    schedule::Trigger,
+    Door, GameState,
+};
+
+/// Moves like its inner behavior, but each turn has a chance to sabotage the player: knocking out
+/// the camera it's on, jamming a door, turning back the clock, draining power or calling in a copy
+/// of itself
+pub struct SaboteurBehavior<BEHAVIOR: EnemyBehavior> {
+    /// How the saboteur gets around
+    inner_behavior: BEHAVIOR,
+    /// The chance of causing some trouble each turn
+    chance: f64,
+    /// How aggressive the saboteur is, scaling down its chance of causing trouble
+    aggression: f64,
+}
+
+impl<BEHAVIOR: EnemyBehavior> SaboteurBehavior<BEHAVIOR> {
+    /// Creates a saboteur that sabotages with the given chance each turn
+    pub fn new(inner_behavior: BEHAVIOR, chance: f64) -> Self {
+        Self {
+            inner_behavior,
+            chance: chance.clamp(0.0, 1.0),
+            aggression: 1.0,
+        }
+    }
+
+    /// Picks some trouble to cause from where the saboteur currently is
+    fn sabotage(
+        &self,
+        curr_state: &GameState,
+        id: EnemyId,
+        rng: &mut dyn RngCore,
+    ) -> Option<Box<dyn SideEffect>> {
+        let room = curr_state.map.get_enemy_room(id)?;
+
+        let effect: Box<dyn SideEffect> = match rng.gen_range(0..5) {
+            0 => Box::new(DisableCamera { room, ticks: 600 }),
+            1 if room == curr_state.office.left => Box::new(JamDoor {
+                door: Door::Left,
+                closed: false,
+                ticks: 300,
+            }),
+            1 if room == curr_state.office.right => Box::new(JamDoor {
+                door: Door::Right,
+                closed: false,
+                ticks: 300,
+            }),
+            1 => Box::new(JamDoor {
+                door: if rng.gen_bool(0.5) {
+                    Door::Left
+                } else {
+                    Door::Right
+                },
+                closed: true,
+                ticks: 300,
+            }),
+            2 => Box::new(RewindTime(300)),
+            3 => Box::new(DrainPower(10_000)),
+            _ => Box::new(SpawnCopy(id)),
+        };
+
+        Some(effect)
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for SaboteurBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let mut actions = self.inner_behavior.tick(curr_state, id, rng);
+
+        if rng.gen_bool(self.chance * self.aggression) {
+            if let Some(effect) = self.sabotage(curr_state, id, rng) {
+                actions.push(Action::Special(effect));
+            }
+        }
+
+        actions
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.aggression = aggression.clamp(0.0, 1.0);
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> serde_json::Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: serde_json::Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
+}
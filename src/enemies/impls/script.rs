@@ -0,0 +1,220 @@
+//! An enemy implementation whose turns are decided by a Rhai script, so enemies can be prototyped
+//! from a roster file without rebuilding the game
+//!
+//! Every turn the script is run with a read-only `view` of the game in scope:
+//!
+//! | Field                                        | Meaning                                       |
+//! |----------------------------------------------|-----------------------------------------------|
+//! | `room`                                       | The room the enemy is in                      |
+//! | `neighbours`                                 | Rooms connected to the enemy's room           |
+//! | `path`                                       | The shortest path to the office, from `room`  |
+//! | `office`, `left_entrance`, `right_entrance`  | The office and the rooms on either side of it |
+//! | `left_closed`, `right_closed`, `cameras_on`  | Whether the doors are closed and cameras on   |
+//! | `watched`                                    | Whether the player is watching `room`         |
+//! | `hour`, `ticks`                              | How far into the night we are                 |
+//! | `power`                                      | The percentage of power left                  |
+//! | `random`                                     | A random number from 0 up to (not including) 1|
+//!
+//! Rooms are plain integers. The script's last expression is the enemy's actions for the turn, as
+//! an array made up of room numbers to move to, `"attack"` or `"nothing"`
+//!
+//! A script can also define `fn wake_on(view)` to be woken up before its next turn, returning an
+//! array made up of `"left_opened"`, `"right_opened"` or `"camera_changed"`. Its `view.random` is
+//! always 0
+
+use rand::{Rng, RngCore};
+use rhai::{Array, CallFnOptions, Dynamic, Engine, Map, Scope, AST, INT};
+use slotmap::{Key, KeyData};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    map::RoomId,
+    schedule::Trigger,
+    Door, GameState,
+};
+
+/// How many operations a script may run in a single turn before it's cut off, so a runaway script
+/// can't freeze the game
+const MAX_OPERATIONS: u64 = 50_000;
+
+/// Runs a script every turn to decide what to do
+pub struct ScriptBehavior {
+    /// The interpreter the script runs in
+    engine: Engine,
+    /// The compiled script
+    script: AST,
+}
+
+impl ScriptBehavior {
+    /// Compiles a script into a behavior, failing if it isn't valid Rhai
+    pub fn new(source: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let script = engine.compile(source).map_err(|err| err.to_string())?;
+
+        Ok(Self { engine, script })
+    }
+}
+
+impl EnemyBehavior for ScriptBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+
+        let mut scope = Scope::new();
+        scope.push_constant("view", view(curr_state, room, rng.gen_range(0.0..1.0)));
+
+        // A script that errors out just loses its turn
+        let actions = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.script)
+            .unwrap_or_default();
+        let actions = match actions.try_cast::<Array>() {
+            Some(actions) => actions,
+            None => return vec![Action::Nothing],
+        };
+
+        actions
+            .into_iter()
+            .map(|action| to_action(curr_state, action))
+            .collect()
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![];
+        };
+
+        // Scripts without a `wake_on` function are happy to wait
+        let triggers = self
+            .engine
+            .call_fn_with_options::<Array>(
+                CallFnOptions::new().eval_ast(false),
+                &mut Scope::new(),
+                &self.script,
+                "wake_on",
+                (view(curr_state, room, 0.0),),
+            )
+            .unwrap_or_default();
+
+        triggers
+            .into_iter()
+            .filter_map(|trigger| match trigger.try_cast::<String>()?.as_str() {
+                "left_opened" => Some(Trigger::DoorOpened(Door::Left)),
+                "right_opened" => Some(Trigger::DoorOpened(Door::Right)),
+                "camera_changed" => Some(Trigger::CameraChanged),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Turns a room into the number scripts know it by
+fn room_number(room: RoomId) -> INT {
+    room.data().as_ffi() as INT
+}
+
+/// Builds the view of the game a script gets to see
+fn view(curr_state: &GameState, room: RoomId, random: f64) -> Map {
+    let rooms = |rooms: &[RoomId]| -> Array {
+        rooms
+            .iter()
+            .map(|room| Dynamic::from_int(room_number(*room)))
+            .collect()
+    };
+    let office = &curr_state.office;
+    let path = curr_state
+        .map
+        .generate_path(room, office.root)
+        .unwrap_or_default();
+
+    let mut view = Map::new();
+    view.insert("room".into(), room_number(room).into());
+    view.insert(
+        "neighbours".into(),
+        rooms(curr_state.map.0[room].connections()).into(),
+    );
+    view.insert("path".into(), rooms(&path).into());
+    view.insert("office".into(), room_number(office.root).into());
+    view.insert("left_entrance".into(), room_number(office.left).into());
+    view.insert("right_entrance".into(), room_number(office.right).into());
+    view.insert(
+        "left_closed".into(),
+        curr_state.door_closed(Door::Left).into(),
+    );
+    view.insert(
+        "right_closed".into(),
+        curr_state.door_closed(Door::Right).into(),
+    );
+    view.insert("cameras_on".into(), curr_state.cameras_on().into());
+    view.insert("watched".into(), curr_state.is_watched(room).into());
+    view.insert("hour".into(), (curr_state.hour() as INT).into());
+    view.insert("ticks".into(), (curr_state.ticks as INT).into());
+    view.insert("power".into(), curr_state.power_percent().into());
+    view.insert("random".into(), random.into());
+
+    view
+}
+
+/// Reads one of a script's actions, ignoring anything it doesn't recognise
+fn to_action(curr_state: &GameState, action: Dynamic) -> Action {
+    if let Ok(room) = action.as_int() {
+        let room: RoomId = KeyData::from_ffi(room as u64).into();
+
+        if room != curr_state.office.root && curr_state.map.0.contains_key(room) {
+            return Action::Move(room);
+        }
+    } else if let Some(action) = action.try_cast::<String>() {
+        if action == "attack" {
+            return Action::Attack;
+        }
+    }
+
+    Action::Nothing
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enemies::action::{Action, EnemyBehavior},
+        schedule::Trigger,
+        Door, GameState,
+    };
+
+    use super::ScriptBehavior;
+
+    #[test]
+    fn scripts_decide_the_enemys_actions() {
+        let mut script = ScriptBehavior::new(
+            r#"
+                if view.room == view.left_entrance && !view.left_closed {
+                    ["attack"]
+                } else if view.path.len() > 2 {
+                    [view.path[1]]
+                } else {
+                    ["nothing"]
+                }
+
+                fn wake_on(view) {
+                    if view.room == view.left_entrance { ["left_opened"] } else { [] }
+                }
+            "#,
+        )
+        .expect("Compile script");
+
+        let (mut game, [id], mut rng) = GameState::test_night(0x5C21);
+
+        game.map.register_enemy(id, game.office.left);
+        assert!(script.tick(&game, id, &mut rng) == [Action::Attack]);
+        assert_eq!(script.wake_on(&game, id), [Trigger::DoorOpened(Door::Left)]);
+
+        game.toggle_door(Door::Left);
+        assert!(script.tick(&game, id, &mut rng) == [Action::Nothing]);
+
+        assert!(ScriptBehavior::new("[").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! An enemy implementation that only creeps closer while the cameras are off
+
+use rand::RngCore;
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    GameState,
+};
+
+/// Advances a room towards the office each turn the cameras are off, but backs away from the
+/// office whenever it catches the cameras on
+#[derive(Default)]
+pub struct ShyBehavior;
+
+impl EnemyBehavior for ShyBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, _rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+        let office = curr_state.office.root;
+        let distance = |from| {
+            curr_state
+                .map
+                .generate_path(from, office)
+                .map_or(usize::MAX, |path| path.len())
+        };
+
+        if curr_state.cameras_on() {
+            let here = distance(room);
+            let further = curr_state.map.0[room]
+                .connections()
+                .iter()
+                .copied()
+                .filter(|next| *next != office)
+                .max_by_key(|next| distance(*next));
+
+            match further {
+                Some(next) if distance(next) > here => vec![Action::Move(next)],
+                _ => vec![Action::Nothing],
+            }
+        } else {
+            match curr_state.map.generate_path(room, office) {
+                Some(path) if path.len() <= 2 => vec![Action::Attack],
+                Some(path) => vec![Action::Move(path[1])],
+                None => vec![Action::Nothing],
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enemies::action::{Action, EnemyBehavior},
+        GameState,
+    };
+
+    use super::ShyBehavior;
+
+    #[test]
+    fn shy_enemy_only_comes_closer_with_the_cameras_off() {
+        let (game, [id], mut rng) = GameState::test_night(0x5A1);
+        let mut game = game.with_enemies(&[id], &mut rng);
+        let room = game.map.get_enemy_room(id).unwrap();
+        let distances = game.map.distances_to(game.office.root);
+        let mut shy = ShyBehavior;
+
+        match shy.tick(&game, id, &mut rng)[..] {
+            [Action::Move(next)] => assert!(distances[next] < distances[room]),
+            _ => panic!("Expected a move"),
+        }
+
+        game.toggle_cameras();
+        match shy.tick(&game, id, &mut rng)[..] {
+            [Action::Move(next)] => assert!(distances[next] > distances[room]),
+            [Action::Nothing] => {}
+            _ => panic!("Expected to back away or stay put"),
+        }
+
+        game.map.move_enemy_out_of(room, id);
+        game.map.move_enemy_to(game.office.right, id);
+        assert!(!(shy.tick(&game, id, &mut rng) == [Action::Attack]));
+
+        game.toggle_cameras();
+        assert!(shy.tick(&game, id, &mut rng) == [Action::Attack]);
+    }
+}
@@ -0,0 +1,89 @@
+//! An enemy implementation that freezes whenever the player is watching it
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    GameState,
+};
+
+/// Won't move an inch while the player has a camera on its room, but covers several rooms at a
+/// time towards the office whenever nobody's looking
+#[derive(Serialize, Deserialize)]
+pub struct StalkerBehavior {
+    /// How many rooms to cover in a turn while unwatched
+    #[serde(default = "default_steps")]
+    steps: usize,
+}
+
+/// Stalkers move two rooms at a time unless told otherwise
+fn default_steps() -> usize {
+    2
+}
+
+impl Default for StalkerBehavior {
+    fn default() -> Self {
+        Self::new(default_steps())
+    }
+}
+
+impl StalkerBehavior {
+    /// Creates a stalker that covers `steps` rooms a turn while unwatched
+    pub fn new(steps: usize) -> Self {
+        Self { steps }
+    }
+}
+
+impl EnemyBehavior for StalkerBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, _rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+
+        if curr_state.is_watched(room) {
+            return vec![Action::Nothing];
+        }
+
+        match curr_state.map.generate_path(room, curr_state.office.root) {
+            Some(path) if path.len() <= 2 => vec![Action::Attack],
+            // Stop at the entrance rather than walking into the office
+            Some(path) => path[1..path.len() - 1]
+                .iter()
+                .take(self.steps.max(1))
+                .map(|next| Action::Move(*next))
+                .collect(),
+            None => vec![Action::Nothing],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enemies::action::{Action, EnemyBehavior},
+        GameState,
+    };
+
+    use super::StalkerBehavior;
+
+    #[test]
+    fn stalker_freezes_while_watched() {
+        let (game, [id], mut rng) = GameState::test_night(0x57A1);
+        let mut game = game.with_enemies(&[id], &mut rng);
+        let room = game.map.get_enemy_room(id).unwrap();
+        let mut stalker = StalkerBehavior::default();
+
+        let unwatched = stalker.tick(&game, id, &mut rng);
+        assert!(matches!(unwatched[..], [Action::Move(_), Action::Move(_)]));
+
+        game.view_camera(room);
+        assert!(stalker.tick(&game, id, &mut rng).len() == 2);
+
+        game.toggle_cameras();
+        assert!(stalker.tick(&game, id, &mut rng) == [Action::Nothing]);
+    }
+}
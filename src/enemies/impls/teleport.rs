@@ -0,0 +1,124 @@
+//! A behavior implementation that every so often skips the hallways altogether
+
+use rand::{seq::IteratorRandom, Rng, RngCore};
+use serde_json::Value;
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        effects::CameraStatic,
+        EnemyId,
+    },
+    schedule::Trigger,
+    GameState,
+};
+
+/// Moves like its inner behavior, but each turn has a chance to vanish and reappear somewhere else
+/// entirely, leaving a burst of static on the cameras of both rooms
+pub struct TeleportBehavior<BEHAVIOR: EnemyBehavior> {
+    /// How the enemy gets around when it isn't teleporting
+    inner_behavior: BEHAVIOR,
+    /// The chance of teleporting each turn
+    chance: f64,
+    /// Whether to only teleport to spawn points, rather than anywhere on the map
+    to_spawn: bool,
+    /// How aggressive the enemy is, scaling down its chance of teleporting
+    aggression: f64,
+}
+
+impl<BEHAVIOR: EnemyBehavior> TeleportBehavior<BEHAVIOR> {
+    /// Creates a teleporter that teleports with the given chance each turn, either anywhere that
+    /// isn't the office or only to spawn points
+    pub fn new(inner_behavior: BEHAVIOR, chance: f64, to_spawn: bool) -> Self {
+        Self {
+            inner_behavior,
+            chance: chance.clamp(0.0, 1.0),
+            to_spawn,
+            aggression: 1.0,
+        }
+    }
+}
+
+impl<BEHAVIOR: EnemyBehavior> EnemyBehavior for TeleportBehavior<BEHAVIOR> {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+
+        if !rng.gen_bool(self.chance * self.aggression) {
+            return self.inner_behavior.tick(curr_state, id, rng);
+        }
+
+        let to = if self.to_spawn {
+            curr_state.spawn_points.iter().copied().choose(rng)
+        } else {
+            curr_state
+                .map
+                .0
+                .keys()
+                .filter(|to| *to != curr_state.office.root)
+                .choose(rng)
+        };
+
+        match to {
+            Some(to) if to != room => vec![
+                Action::Special(Box::new(CameraStatic(room))),
+                Action::Move(to),
+                Action::Special(Box::new(CameraStatic(to))),
+            ],
+            _ => vec![Action::Nothing],
+        }
+    }
+
+    fn wake_on(&self, curr_state: &GameState, id: EnemyId) -> Vec<Trigger> {
+        self.inner_behavior.wake_on(curr_state, id)
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.aggression = aggression.clamp(0.0, 1.0);
+        self.inner_behavior.set_aggression(aggression)
+    }
+
+    fn save(&self) -> Value {
+        self.inner_behavior.save()
+    }
+
+    fn load(&mut self, state: Value) -> serde_json::Result<()> {
+        self.inner_behavior.load(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{
+        enemies::{impls::generic::StraightPathBehavior, EnemyId, Freak},
+        events::GameEvent,
+        GameState,
+    };
+
+    use super::TeleportBehavior;
+
+    #[test]
+    fn teleporter_leaves_static_behind() {
+        let (mut game, [], mut rng) = GameState::test_night(0x7E1E);
+        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+        let behavior = TeleportBehavior::new(StraightPathBehavior::default(), 1.0, true);
+        let id = enemies.insert(Freak::new("teleporter", 1..2, behavior));
+        game.map.register_enemy(id, game.office.left);
+
+        // Once to wake up, once to act
+        enemies[id].tick(id, &mut game, &mut rng);
+        enemies[id].tick(id, &mut game, &mut rng);
+
+        let room = game.map.get_enemy_room(id).unwrap();
+        assert!(game.spawn_points.contains(&room));
+
+        let events = game.drain_events();
+        assert!(events.contains(&GameEvent::CameraStatic { room }));
+        assert!(events.contains(&GameEvent::CameraStatic {
+            room: game.office.left
+        }));
+    }
+}
@@ -0,0 +1,68 @@
+//! An enemy implementation that crawls through the vents, skipping over rooms on its way to the
+//! office
+
+use rand::RngCore;
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    GameState,
+};
+
+/// Takes the shortest path to the office but crawls through the vents two rooms at a time, so it
+/// never shows up in the room between. It can't crawl into the office itself, so it still has to
+/// drop out at an entrance and attack from there
+#[derive(Default)]
+pub struct VentBehavior;
+
+impl EnemyBehavior for VentBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, _rng: &mut dyn RngCore) -> Vec<Action> {
+        let path = curr_state
+            .map
+            .get_enemy_room(id)
+            .and_then(|room| curr_state.map.generate_path(room, curr_state.office.root));
+
+        match path {
+            Some(path) if path.len() <= 2 => vec![Action::Attack],
+            Some(path) => vec![Action::Move(path[2.min(path.len() - 2)])],
+            None => vec![Action::Nothing],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enemies::action::{Action, EnemyBehavior},
+        GameState,
+    };
+
+    use super::VentBehavior;
+
+    #[test]
+    fn vent_crawler_skips_rooms_but_drops_out_at_the_entrance() {
+        let (mut game, [id], mut rng) = GameState::test_night(0x7E27);
+        let office = game.office.root;
+        let far = game
+            .map
+            .distances_to(office)
+            .iter()
+            .find(|(_, distance)| **distance >= 3)
+            .map(|(room, _)| room)
+            .unwrap();
+        game.map.register_enemy(id, far);
+        let path = game.map.generate_path(far, office).unwrap();
+
+        let mut vents = VentBehavior;
+        match vents.tick(&game, id, &mut rng)[..] {
+            [Action::Move(to)] => assert_eq!(to, path[2]),
+            _ => panic!("Expected a move"),
+        }
+
+        game.map.move_enemy_out_of(far, id);
+        game.map.move_enemy_to(game.office.left, id);
+        assert!(vents.tick(&game, id, &mut rng) == [Action::Attack]);
+    }
+}
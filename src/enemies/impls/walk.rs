@@ -0,0 +1,125 @@
+//! An enemy implementation that wanders at random, but is drawn towards the office
+
+use rand::{distributions::WeightedIndex, Rng, RngCore};
+use serde::Deserialize;
+
+use crate::{
+    enemies::{
+        action::{Action, EnemyBehavior},
+        EnemyId,
+    },
+    GameState,
+};
+
+/// Every turn, the enemy picks a random neighbouring room, with rooms closer to the office more
+/// likely to be picked. The temperature decides how much closer matters: a high temperature wanders
+/// almost aimlessly, while one near 0 heads straight for the office. Walking into the office is an
+/// attack
+#[derive(Deserialize)]
+pub struct WalkBehavior {
+    /// How aimless the walk is, from 0 (relentless) upwards
+    #[serde(default = "default_temperature")]
+    temperature: f64,
+    /// How aggressive the walker is. Less aggressive walkers are more aimless
+    #[serde(skip, default = "usual_aggression")]
+    aggression: f64,
+}
+
+/// How aimless a walk is if the roster doesn't say otherwise
+fn default_temperature() -> f64 {
+    1.0
+}
+
+/// Walkers walk at their usual temperature until told otherwise
+fn usual_aggression() -> f64 {
+    1.0
+}
+
+impl Default for WalkBehavior {
+    fn default() -> Self {
+        Self::new(default_temperature())
+    }
+}
+
+impl WalkBehavior {
+    /// Creates a walk with the given temperature
+    pub fn new(temperature: f64) -> Self {
+        Self {
+            temperature,
+            aggression: usual_aggression(),
+        }
+    }
+}
+
+impl EnemyBehavior for WalkBehavior {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+        let office = curr_state.office.root;
+        let distances = curr_state.map.distances_to(office);
+
+        // Rooms that can't reach the office at all are never worth walking into
+        let rooms: Vec<_> = curr_state.map.0[room]
+            .connections()
+            .iter()
+            .filter_map(|next| Some((*next, *distances.get(*next)?)))
+            .collect();
+        let Some(closest) = rooms.iter().map(|(_, distance)| *distance).min() else {
+            return vec![Action::Nothing];
+        };
+
+        let temperature = self.temperature / self.aggression.max(f64::EPSILON);
+        let weights = rooms.iter().map(|(_, distance)| {
+            if temperature > 0.0 {
+                (-((distance - closest) as f64) / temperature).exp()
+            } else {
+                (*distance == closest) as u8 as f64
+            }
+        });
+        let goto = match WeightedIndex::new(weights) {
+            Ok(weights) => rooms[rng.sample(weights)].0,
+            Err(_) => return vec![Action::Nothing],
+        };
+
+        if goto == office {
+            vec![Action::Attack]
+        } else {
+            vec![Action::Move(goto)]
+        }
+    }
+
+    fn set_aggression(&mut self, aggression: f64) {
+        self.aggression = aggression.clamp(0.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enemies::action::{Action, EnemyBehavior},
+        GameState,
+    };
+
+    use super::WalkBehavior;
+
+    #[test]
+    fn cold_walks_head_straight_for_the_office() {
+        let (game, [id], mut rng) = GameState::test_night(0x3A1C);
+        let mut game = game.with_enemies(&[id], &mut rng);
+        let room = game.map.get_enemy_room(id).unwrap();
+        let distances = game.map.distances_to(game.office.root);
+
+        let mut walk = WalkBehavior::new(0.0);
+        for _ in 0..10 {
+            match walk.tick(&game, id, &mut rng)[..] {
+                [Action::Move(next)] => assert!(distances[next] < distances[room]),
+                _ => panic!("Expected a move"),
+            }
+        }
+
+        game.map.move_enemy_out_of(room, id);
+        game.map.move_enemy_to(game.office.left, id);
+        assert!(walk.tick(&game, id, &mut rng) == [Action::Attack]);
+    }
+}
@@ -0,0 +1,368 @@
+//! Declarative enemy rosters, parsed from JSON into enemies through a registry of named behaviors
+
+use std::{cell::RefCell, collections::HashMap, ops::Range};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    action::EnemyBehavior,
+    impls::{
+        ambush::AmbushBehavior,
+        banger::BangerBehavior,
+        combinators::{
+            AfterHourBehavior, AlternateBehavior, ChanceBehavior, CooldownBehavior,
+            SequenceBehavior, WhenCamerasOffBehavior,
+        },
+        double::DoubleBehavior,
+        drainer::DrainerBehavior,
+        generic::StraightPathBehavior,
+        learning::LearningBehavior,
+        pack::{PackBehavior, SharedBlackboard},
+        random::RandomBehavior,
+        saboteur::SaboteurBehavior,
+        shy::ShyBehavior,
+        stalker::StalkerBehavior,
+        teleport::TeleportBehavior,
+        vent::VentBehavior,
+        walk::WalkBehavior,
+    },
+    tree::BehaviorTree,
+    Freak, Retreat,
+};
+
+#[cfg(feature = "scripting")]
+use super::impls::script::ScriptBehavior;
+
+/// The roster the game ships with, kept alongside the enemy assets
+const DEFAULT_ROSTER: &str = include_str!("../../docs/enemies/roster.json");
+
+/// Builds a behavior from the parameters given to it in a behavior spec
+pub type BehaviorBuilder = fn(&BehaviorRegistry, &Value) -> Result<Box<dyn EnemyBehavior>, String>;
+
+/// A lookup of behavior names to how they are built. A behavior spec is an object with a single
+/// behavior name as its key and that behavior's parameters as its value, for example
+/// `{"double": {"straight": {}}}`
+///
+/// Pack members only share a blackboard when they're built through the same registry, so a game
+/// keeps the registry that built its enemies for building copies of them
+pub struct BehaviorRegistry {
+    /// How each named behavior is built
+    builders: HashMap<&'static str, BehaviorBuilder>,
+    /// The blackboard each named pack shares, handed out as pack members are built
+    packs: RefCell<HashMap<String, SharedBlackboard>>,
+}
+
+impl Default for BehaviorRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            builders: HashMap::new(),
+            packs: RefCell::default(),
+        };
+
+        registry.register("straight", |_, _| {
+            Ok(Box::new(StraightPathBehavior::default()))
+        });
+        registry.register("random", |_, _| Ok(Box::new(RandomBehavior)));
+        registry.register("walk", |_, params| {
+            let walk: WalkBehavior =
+                serde_json::from_value(params.clone()).map_err(|err| err.to_string())?;
+            Ok(Box::new(walk))
+        });
+        registry.register("double", |registry, inner| {
+            Ok(Box::new(DoubleBehavior::new(registry.build(inner)?)))
+        });
+        registry.register("vents", |_, _| Ok(Box::new(VentBehavior)));
+        registry.register("learning", |_, _| Ok(Box::new(LearningBehavior::default())));
+        registry.register("shy", |_, _| Ok(Box::new(ShyBehavior)));
+        registry.register("stalker", |_, params| {
+            let stalker: StalkerBehavior =
+                serde_json::from_value(params.clone()).map_err(|err| err.to_string())?;
+            Ok(Box::new(stalker))
+        });
+        registry.register("drainer", |registry, params| {
+            let inner = registry.build(param(params, "inner")?)?;
+            let drain = params
+                .get("drain")
+                .and_then(Value::as_i64)
+                .unwrap_or(25_000);
+
+            Ok(Box::new(DrainerBehavior::new(inner, drain as i32)))
+        });
+        registry.register("ambush", |registry, params| {
+            let inner = registry.build(param(params, "inner")?)?;
+            let lurk = match params.get("lurk") {
+                Some(lurk) => {
+                    serde_json::from_value(lurk.clone()).map_err(|err| err.to_string())?
+                }
+                None => 300..900,
+            };
+
+            Ok(Box::new(AmbushBehavior::new(inner, lurk)))
+        });
+        registry.register("banger", |registry, params| {
+            let inner = registry.build(param(params, "inner")?)?;
+            let drain = params.get("drain").and_then(Value::as_i64).unwrap_or(50);
+            let ticks = params.get("ticks").and_then(Value::as_u64).unwrap_or(300);
+
+            Ok(Box::new(BangerBehavior::new(inner, drain as i32, ticks)))
+        });
+        registry.register("pack", |registry, params| {
+            let name = param(params, "name")?
+                .as_str()
+                .ok_or("Behavior's `name` parameter should be a string")?;
+            let patience = params.get("patience").and_then(Value::as_u64).unwrap_or(3);
+
+            Ok(Box::new(PackBehavior::new(registry.pack(name), patience)))
+        });
+        registry.register("saboteur", |registry, params| {
+            let inner = registry.build(param(params, "inner")?)?;
+            let chance = params.get("chance").and_then(Value::as_f64).unwrap_or(0.25);
+
+            Ok(Box::new(SaboteurBehavior::new(inner, chance)))
+        });
+        registry.register("teleport", |registry, params| {
+            let inner = registry.build(param(params, "inner")?)?;
+            let chance = params.get("chance").and_then(Value::as_f64).unwrap_or(0.1);
+            let to_spawn = params
+                .get("to_spawn")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+
+            Ok(Box::new(TeleportBehavior::new(inner, chance, to_spawn)))
+        });
+        registry.register("chance", |registry, params| {
+            let chance = number(params, "chance")?;
+            let inner = registry.build(param(params, "inner")?)?;
+
+            Ok(Box::new(ChanceBehavior::new(chance, inner)))
+        });
+        registry.register("sequence", |registry, params| {
+            let behaviors = list(params)?
+                .iter()
+                .map(|spec| registry.build(spec))
+                .collect::<Result<_, _>>()?;
+
+            Ok(Box::new(SequenceBehavior::new(behaviors)))
+        });
+        registry.register("cameras_off", |registry, inner| {
+            Ok(Box::new(WhenCamerasOffBehavior::new(
+                registry.build(inner)?,
+            )))
+        });
+        registry.register("after_hour", |registry, params| {
+            let hour = number(params, "hour")? as u64;
+            let inner = registry.build(param(params, "inner")?)?;
+
+            Ok(Box::new(AfterHourBehavior::new(hour, inner)))
+        });
+        registry.register("alternate", |registry, params| match list(params)? {
+            [first, second] => Ok(Box::new(AlternateBehavior::new(
+                registry.build(first)?,
+                registry.build(second)?,
+            ))),
+            _ => Err("`alternate` needs exactly two behaviors".to_string()),
+        });
+        registry.register("cooldown", |registry, params| {
+            let turns = number(params, "turns")? as u64;
+            let inner = registry.build(param(params, "inner")?)?;
+
+            Ok(Box::new(CooldownBehavior::new(turns, inner)))
+        });
+        #[cfg(feature = "scripting")]
+        registry.register("script", |_, params| {
+            let source = param(params, "source")?
+                .as_str()
+                .ok_or("Behavior's `source` parameter should be a string")?;
+
+            Ok(Box::new(ScriptBehavior::new(source)?))
+        });
+        registry.register("tree", |_, params| {
+            let tree: BehaviorTree =
+                serde_json::from_value(params.clone()).map_err(|err| err.to_string())?;
+            Ok(Box::new(tree))
+        });
+
+        registry
+    }
+}
+
+impl BehaviorRegistry {
+    /// Registers a new behavior under the given name
+    pub fn register(&mut self, name: &'static str, builder: BehaviorBuilder) {
+        self.builders.insert(name, builder);
+    }
+
+    /// The blackboard shared by every member of the named pack built through this registry
+    pub fn pack(&self, name: &str) -> SharedBlackboard {
+        self.packs
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Builds a behavior from its spec
+    pub fn build(&self, spec: &Value) -> Result<Box<dyn EnemyBehavior>, String> {
+        let (name, params) = match spec.as_object() {
+            Some(spec) if spec.len() == 1 => spec.iter().next().unwrap(),
+            _ => return Err(format!("Behavior spec should name one behavior: {spec}")),
+        };
+
+        let builder = self
+            .builders
+            .get(name.as_str())
+            .ok_or_else(|| format!("Unknown behavior `{name}`"))?;
+
+        builder(self, params)
+    }
+}
+
+/// Gets a named parameter out of a behavior's parameters
+fn param<'a>(params: &'a Value, name: &str) -> Result<&'a Value, String> {
+    params
+        .get(name)
+        .ok_or_else(|| format!("Behavior is missing its `{name}` parameter"))
+}
+
+/// Gets a named number parameter out of a behavior's parameters
+fn number(params: &Value, name: &str) -> Result<f64, String> {
+    param(params, name)?
+        .as_f64()
+        .ok_or_else(|| format!("Behavior's `{name}` parameter should be a number"))
+}
+
+/// Reads a behavior's parameters as a list of behavior specs
+fn list(params: &Value) -> Result<&[Value], String> {
+    params
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or_else(|| format!("Expected a list of behaviors: {params}"))
+}
+
+/// A single enemy as described in a roster file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterEntry {
+    /// The enemy's name, which is also the folder its assets live in
+    pub name: String,
+    /// The range of ticks between the enemy's actions on its first night
+    pub cooldown: Range<u64>,
+    /// The first campaign night the enemy shows up on
+    #[serde(default = "first_night")]
+    pub night: u8,
+    /// The spec for the enemy's behavior
+    pub behavior: Value,
+    /// Where the enemy goes when its attack is blocked
+    #[serde(default)]
+    pub retreat: Retreat,
+}
+
+/// Enemies show up from the first night unless their entry says otherwise
+fn first_night() -> u8 {
+    1
+}
+
+impl RosterEntry {
+    /// Builds the enemy this entry describes
+    pub fn build(&self, registry: &BehaviorRegistry) -> Result<Freak, String> {
+        if self.cooldown.is_empty() {
+            return Err(format!("`{}` has an empty cooldown range", self.name));
+        }
+
+        Freak::from_spec(
+            self.name.clone(),
+            self.cooldown.clone(),
+            self.behavior.clone(),
+            registry,
+        )
+        .map(|enemy| enemy.with_retreat(self.retreat))
+    }
+}
+
+/// Every enemy that can take part in a game
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Roster(Vec<RosterEntry>);
+
+impl Default for Roster {
+    fn default() -> Self {
+        Self::parse(DEFAULT_ROSTER).expect("The built-in roster is valid")
+    }
+}
+
+impl Roster {
+    /// Parses a roster from JSON, making sure every entry's behavior can actually be built
+    pub fn parse(roster: &str) -> Result<Self, String> {
+        let roster: Self = serde_json::from_str(roster).map_err(|err| err.to_string())?;
+        roster.build(&BehaviorRegistry::default())?;
+
+        Ok(roster)
+    }
+
+    /// All entries in the roster
+    pub fn entries(&self) -> &[RosterEntry] {
+        &self.0
+    }
+
+    /// Builds every enemy in the roster, in order
+    pub fn build(&self, registry: &BehaviorRegistry) -> Result<Vec<Freak>, String> {
+        self.0.iter().map(|entry| entry.build(registry)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{BehaviorRegistry, Roster};
+
+    #[test]
+    fn roster_specs_are_parsed_into_enemies() {
+        let roster = Roster::parse(
+            r#"[{
+                "name": "remington",
+                "cooldown": { "start": 10, "end": 20 },
+                "behavior": { "double": { "cooldown": { "turns": 2, "inner": { "sequence": [
+                    { "chance": { "chance": 0.5, "inner": { "straight": {} } } },
+                    { "alternate": [{ "random": {} }, { "cameras_off": { "straight": {} } }] },
+                    { "after_hour": { "hour": 3, "inner": { "vents": {} } } }
+                ] } } } }
+            }]"#,
+        )
+        .expect("Parse roster");
+
+        assert_eq!(roster.entries()[0].night, 1);
+        assert!(Roster::default().entries().len() >= 3);
+    }
+
+    #[test]
+    fn unknown_behaviors_are_rejected() {
+        let roster = r#"[{
+            "name": "frank",
+            "cooldown": { "start": 10, "end": 20 },
+            "behavior": { "no_such_behavior": {} }
+        }]"#;
+
+        assert!(Roster::parse(roster).is_err());
+    }
+
+    #[test]
+    fn behaviors_missing_params_are_rejected() {
+        let registry = BehaviorRegistry::default();
+
+        assert!(registry.build(&json!({ "teleport": {} })).is_err());
+        assert!(registry
+            .build(&json!({ "teleport": { "inner": { "straight": {} } } }))
+            .is_ok());
+
+        assert!(registry.build(&json!({ "banger": {} })).is_err());
+        assert!(registry
+            .build(&json!({ "banger": { "inner": { "cameras_off": { "straight": {} } } } }))
+            .is_ok());
+
+        assert!(registry.build(&json!({ "ambush": { "lurk": 5 } })).is_err());
+        assert!(registry
+            .build(&json!({ "ambush": { "inner": { "walk": {} } } }))
+            .is_ok());
+    }
+}
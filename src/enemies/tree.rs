@@ -0,0 +1,294 @@
+//! A behavior tree engine for authoring enemy AI as data. Trees are made of selectors, sequences,
+//! decorators, conditions that query the game state, and leaf actions that produce enemy actions
+
+use rand::{seq::SliceRandom, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{map::RoomId, schedule::Trigger, Door, GameState};
+
+use super::{
+    action::{Action, EnemyBehavior},
+    effects::{DisableCamera, DrainPower},
+    EnemyId,
+};
+
+/// A node in a behavior tree
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Node {
+    /// Runs each child in order until one succeeds, succeeding if any of them do
+    Selector(Vec<Node>),
+    /// Runs each child in order until one fails, succeeding only if all of them do
+    Sequence(Vec<Node>),
+    /// Changes how its child's result is reported
+    Decorate {
+        /// How the result is changed
+        decorator: Decorator,
+        /// The decorated node
+        child: Box<Node>,
+    },
+    /// Checks something about the game, succeeding if it holds
+    Condition(Condition),
+    /// Does something, succeeding if it could be done
+    Action(Leaf),
+}
+
+/// Ways a decorator can change its child's result
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decorator {
+    /// Succeeds when the child fails and fails when it succeeds
+    Invert,
+    /// Always succeeds, whatever the child did
+    Succeed,
+    /// Only runs the child with the given chance, failing otherwise
+    Chance(f64),
+}
+
+/// Questions a condition node can ask about the game
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Is the given door closed?
+    DoorClosed(Door),
+    /// Are the cameras on?
+    CamerasOn,
+    /// Is it at least this hour, counting from 0 at 12 AM?
+    HourAtLeast(u64),
+    /// Is the power below this percentage?
+    PowerBelow(f64),
+    /// Is the enemy standing at one of the office's entrances?
+    AtEntrance,
+    /// Is the player watching the enemy's room on camera?
+    Watched,
+}
+
+/// Actions a leaf node can take
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Leaf {
+    /// Attack the office, only possible from an entrance
+    Attack,
+    /// Move a room closer to the office, short of walking into it
+    Advance,
+    /// Move a room further from the office
+    Retreat,
+    /// Move to a random neighbouring room other than the office
+    Wander,
+    /// Drain this much power
+    DrainPower(i32),
+    /// Knock out the camera in the enemy's room for this many ticks
+    DisableCamera(u64),
+    /// Do nothing, which always succeeds
+    Wait,
+}
+
+/// Everything a node needs to know while the tree is being run
+struct Context<'a> {
+    /// The game being played
+    curr_state: &'a GameState,
+    /// Where that enemy currently is
+    room: RoomId,
+    /// Randomness for decorators and leaves
+    rng: &'a mut dyn RngCore,
+    /// Every action taken so far this turn
+    actions: Vec<Action>,
+}
+
+impl Node {
+    /// Runs the node, returning whether it succeeded
+    fn run(&self, ctx: &mut Context<'_>) -> bool {
+        match self {
+            Node::Selector(children) => children.iter().any(|child| child.run(ctx)),
+            Node::Sequence(children) => children.iter().all(|child| child.run(ctx)),
+            Node::Decorate { decorator, child } => match decorator {
+                Decorator::Invert => !child.run(ctx),
+                Decorator::Succeed => {
+                    child.run(ctx);
+                    true
+                }
+                Decorator::Chance(chance) => {
+                    ctx.rng.gen_bool(chance.clamp(0.0, 1.0)) && child.run(ctx)
+                }
+            },
+            Node::Condition(condition) => condition.holds(ctx),
+            Node::Action(leaf) => match leaf.act(ctx) {
+                Some(action) => {
+                    ctx.actions.push(action);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+impl Condition {
+    /// Checks whether the condition holds right now
+    fn holds(&self, ctx: &Context<'_>) -> bool {
+        let state = ctx.curr_state;
+
+        match self {
+            Condition::DoorClosed(door) => state.door_closed(*door),
+            Condition::CamerasOn => state.cameras_on(),
+            Condition::HourAtLeast(hour) => state.hour() >= *hour,
+            Condition::PowerBelow(percent) => state.power_percent() < *percent,
+            Condition::AtEntrance => {
+                ctx.room == state.office.left || ctx.room == state.office.right
+            }
+            Condition::Watched => state.is_watched(ctx.room),
+        }
+    }
+}
+
+impl Leaf {
+    /// Works out the action this leaf takes, if it can be taken at all
+    fn act(&self, ctx: &mut Context<'_>) -> Option<Action> {
+        let state = ctx.curr_state;
+        let office = state.office.root;
+        let distance = |room| state.map.generate_path(room, office).map(|path| path.len());
+
+        match self {
+            Leaf::Attack => Condition::AtEntrance.holds(ctx).then_some(Action::Attack),
+            Leaf::Advance => state
+                .map
+                .generate_path(ctx.room, office)
+                .filter(|path| path.len() > 2)
+                .map(|path| Action::Move(path[1])),
+            Leaf::Retreat => {
+                let here = distance(ctx.room);
+                state.map.0[ctx.room]
+                    .connections()
+                    .iter()
+                    .copied()
+                    .filter(|room| distance(*room) > here)
+                    .max_by_key(|room| distance(*room))
+                    .map(Action::Move)
+            }
+            Leaf::Wander => {
+                let rooms: Vec<_> = state.map.0[ctx.room]
+                    .connections()
+                    .iter()
+                    .copied()
+                    .filter(|room| *room != office)
+                    .collect();
+                rooms.choose(ctx.rng).copied().map(Action::Move)
+            }
+            Leaf::DrainPower(amount) => Some(Action::Special(Box::new(DrainPower(*amount)))),
+            Leaf::DisableCamera(ticks) => Some(Action::Special(Box::new(DisableCamera {
+                room: ctx.room,
+                ticks: *ticks,
+            }))),
+            Leaf::Wait => Some(Action::Nothing),
+        }
+    }
+}
+
+/// An enemy behavior driven by a behavior tree, run from the root every turn. Whatever actions
+/// the tree's leaves take along the way are the enemy's actions for the turn
+#[derive(Deserialize)]
+pub struct BehaviorTree {
+    /// The tree's root node
+    root: Node,
+    /// What wakes the enemy up to re-run the tree before its next turn. Trees only ever wake up
+    /// early when they ask to, so the player can't hurry them along by fiddling with the office
+    #[serde(default)]
+    wake_on: Vec<Trigger>,
+}
+
+impl BehaviorTree {
+    /// Creates a behavior from a tree that only runs on the enemy's own turns
+    pub fn new(root: Node) -> Self {
+        Self {
+            root,
+            wake_on: vec![],
+        }
+    }
+
+    /// Wakes the enemy up to re-run the tree whenever any of the triggers happen
+    pub fn waking_on(mut self, triggers: Vec<Trigger>) -> Self {
+        self.wake_on = triggers;
+        self
+    }
+}
+
+impl EnemyBehavior for BehaviorTree {
+    fn tick(&mut self, curr_state: &GameState, id: EnemyId, rng: &mut dyn RngCore) -> Vec<Action> {
+        let Some(room) = curr_state.map.get_enemy_room(id) else {
+            return vec![Action::Nothing];
+        };
+
+        let mut ctx = Context {
+            curr_state,
+            room,
+            rng,
+            actions: vec![],
+        };
+        self.root.run(&mut ctx);
+
+        if ctx.actions.is_empty() {
+            vec![Action::Nothing]
+        } else {
+            ctx.actions
+        }
+    }
+
+    fn wake_on(&self, _curr_state: &GameState, _id: EnemyId) -> Vec<Trigger> {
+        self.wake_on.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enemies::{action::Action, roster::BehaviorRegistry},
+        schedule::Trigger,
+        Door, GameState,
+    };
+
+    #[test]
+    fn tree_attacks_only_through_open_doors() {
+        let spec = serde_json::json!({ "tree": { "root": { "selector": [
+            { "sequence": [
+                { "condition": "at_entrance" },
+                { "decorate": {
+                    "decorator": "invert",
+                    "child": { "condition": { "door_closed": "Left" } }
+                } },
+                { "action": "attack" }
+            ] },
+            { "sequence": [{ "condition": "at_entrance" }, { "action": { "drain_power": 500 } }] },
+            { "action": "advance" }
+        ] }, "wake_on": [{ "DoorOpened": "Left" }] } });
+        let mut tree = BehaviorRegistry::default()
+            .build(&spec)
+            .expect("Build tree");
+
+        let (mut game, [id], mut rng) = GameState::test_night(0x7AEE);
+        game.map.register_enemy(id, game.office.left);
+
+        assert!(tree.tick(&game, id, &mut rng) == [Action::Attack]);
+        assert_eq!(tree.wake_on(&game, id), [Trigger::DoorOpened(Door::Left)]);
+
+        game.toggle_door(Door::Left);
+        let actions = tree.tick(&game, id, &mut rng);
+        assert!(matches!(actions[..], [Action::Special(_)]));
+    }
+
+    #[test]
+    fn trees_only_wake_up_when_asked_to() {
+        let spec = serde_json::json!({ "tree": { "root": { "selector": [
+            { "sequence": [{ "condition": { "door_closed": "Left" } }, { "action": "wait" }] },
+            { "sequence": [{ "condition": "watched" }, { "action": "wait" }] },
+            { "action": "advance" }
+        ] } } });
+        let tree = BehaviorRegistry::default()
+            .build(&spec)
+            .expect("Build tree");
+
+        let (mut game, [id], mut rng) = GameState::test_night(0x7AEF);
+        game = game.with_enemies(&[id], &mut rng);
+
+        assert!(tree.wake_on(&game, id).is_empty());
+    }
+}
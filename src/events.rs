@@ -0,0 +1,81 @@
+//! Everything noteworthy that happens during a night, queued up for the frontend to react to
+//! instead of having to poll for it
+
+use serde::{Deserialize, Serialize};
+
+use crate::{enemies::EnemyId, map::RoomId, Door};
+
+/// Something that happened in the game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum GameEvent {
+    /// An enemy moved from one room to another
+    EnemyMoved {
+        /// The enemy that moved
+        enemy: EnemyId,
+        /// Where it was, if it was anywhere yet
+        from: Option<RoomId>,
+        /// Where it is now
+        to: RoomId,
+    },
+    /// An enemy woke up and started moving
+    EnemyWokeUp {
+        /// The enemy that woke up
+        enemy: EnemyId,
+    },
+    /// An enemy tried to get into the office but was shut out
+    AttackBlocked {
+        /// The enemy that was shut out
+        enemy: EnemyId,
+        /// The door it was shut out by, if it even made it to one
+        door: Option<Door>,
+    },
+    /// A door was opened or closed
+    DoorToggled {
+        /// The door
+        door: Door,
+        /// Whether it's closed now
+        closed: bool,
+    },
+    /// An enemy started banging on a door
+    DoorBanged {
+        /// The door being banged on
+        door: Door,
+    },
+    /// The light outside a door was turned on or off
+    LightToggled {
+        /// The door whose light it is
+        door: Door,
+        /// Whether it's on now
+        on: bool,
+    },
+    /// The cameras were turned on or off
+    CamerasToggled {
+        /// Whether they're on now
+        on: bool,
+    },
+    /// A room's camera was knocked out
+    CameraDisabled {
+        /// The room whose camera went dark
+        room: RoomId,
+    },
+    /// A room's camera caught a burst of static
+    CameraStatic {
+        /// The room the static showed up in
+        room: RoomId,
+    },
+    /// The power ran out, opening every door
+    PowerOut,
+    /// The clock struck a new hour
+    HourChanged {
+        /// The hour, counting up from 0 at 12 AM
+        hour: u64,
+    },
+    /// The player made it through the night
+    Won,
+    /// An enemy made it into the office
+    Killed {
+        /// The enemy that got in
+        enemy: EnemyId,
+    },
+}
@@ -1,82 +1,165 @@
 //! The Game State Machine Definition, Creates a Game Session with a number of enemies, a target
 //! time to aim for, an amount of ticks to reach that time, power information, and door states
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+use audio::AudioCue;
+use campaign::{Campaign, DoorHabits};
+use config::NightConfig;
 use enemies::{
-    impls::{double::DoubleBehavior, generic::StraightPathBehavior, random::RandomBehavior},
-    EnemyId, Freak,
+    roster::{BehaviorRegistry, Roster},
+    EnemyId, Freak, Retreat,
 };
+use events::GameEvent;
 use map::{Map, RoomId, RootRoomInfo};
-use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
-use slotmap::SlotMap;
+use rand::{seq::SliceRandom, thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use replay::{Input, Recording, Replay};
+use save::SaveFile;
+use schedule::{Schedule, Trigger};
+use serde::{Deserialize, Serialize};
+use slotmap::{Key, SecondaryMap, SlotMap};
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
+pub mod audio;
+pub mod campaign;
+pub mod config;
 pub mod enemies;
+pub mod events;
 pub mod map;
+pub mod replay;
+pub mod save;
+pub mod schedule;
 
-/// How much power a door being closed draws
-pub const POWER_DRAW_DOOR: i32 = 75;
-/// How much power is idly drawn
-pub const DEFAULT_POWER_DRAW: i32 = 5;
-/// How much power being on the cameras draws
-pub const CAMERA_ON_DRAW: i32 = 15;
-/// How much power you start with
-pub const INITIAL_POWER: i32 = 500_000;
-/// How many game ticks we need to win
-pub const TICKS_PER_HOUR: u64 = 1800;
-/// How many hours do we need to survive
-pub const HOURS_TO_WIN: u64 = 6;
+/// The random number generator every random decision in a game is drawn from. Seeded so a night
+/// can be reproduced exactly, and portable so a seed means the same thing on every platform
+pub type GameRng = ChaCha8Rng;
 
 /// The full driver for a game responsible for holding both the enemies and the game state
 #[wasm_bindgen]
 pub struct Game {
     /// All enemies that exist in the game
     enemies: SlotMap<EnemyId, Freak>,
+    /// The registry the enemies were built from, kept around to build copies of them
+    registry: BehaviorRegistry,
     /// The actual game's state
     state: GameState,
     /// The random number generation
-    rng: ThreadRng,
+    rng: GameRng,
+    /// The seed the random number generation started from
+    seed: u64,
+    /// Every input the player has made so far
+    recording: Recording,
+    /// Inputs still waiting to be replayed, if this game is a replay
+    replay: Replay,
+    /// Which night of the campaign is being played
+    campaign: Campaign,
 }
 
 impl Default for Game {
     fn default() -> Self {
-        let mut rng = thread_rng();
-        let move_rng = thread_rng();
+        Self::with_seed(thread_rng().next_u64())
+    }
+}
 
-        let mut enemies: SlotMap<EnemyId, Freak> = SlotMap::default();
+#[wasm_bindgen]
+impl Game {
+    /// Create a new game whose every random decision is derived from `seed`, so the same seed
+    /// always plays out the same night given the same inputs
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_night_config(seed, NightConfig::default())
+    }
 
-        // Register all enemies we want in the game
-        let enemy_registry: Vec<Freak> = vec![
-            Freak::new("teller", 800..1200, StraightPathBehavior::default()),
-            Freak::new(
-                "remington",
-                800..2500,
-                DoubleBehavior::new(StraightPathBehavior::default()),
-            ),
-            Freak::new("frank", 300..800, RandomBehavior::new(move_rng)),
-        ];
+    /// Create a new seeded game tuned by a `NightConfig` passed in from JS. Any fields the config
+    /// leaves out keep their default values
+    pub fn with_config(seed: u64, config: JsValue) -> Result<Game, String> {
+        let config: NightConfig =
+            serde_wasm_bindgen::from_value(config).map_err(|err| err.to_string())?;
+        Self::start_night(Campaign::default(), seed, config)
+    }
 
-        for enemy in enemy_registry {
-            enemies.insert(enemy);
-        }
+    /// Create a custom night from a map of enemy names to aggression levels from 0 to 20.
+    /// Enemies left out of the map stay asleep all night, while names that aren't on the roster or
+    /// levels above 20 are rejected
+    pub fn custom_night(seed: u64, levels: JsValue) -> Result<Game, String> {
+        let levels: BTreeMap<String, u8> =
+            serde_wasm_bindgen::from_value(levels).map_err(|err| err.to_string())?;
+        Self::with_levels(seed, levels)
+    }
 
-        let state =
-            GameState::default().with_enemies(&enemies.keys().collect::<Vec<_>>(), &mut rng);
+    /// Create a new seeded campaign whose enemies come from a roster file rather than the
+    /// built-in roster
+    pub fn with_roster(seed: u64, roster: &str) -> Result<Game, String> {
+        let campaign = Campaign::default().with_roster(Roster::parse(roster)?);
+        Self::start_night(campaign, seed, NightConfig::default())
+    }
 
-        Self {
-            enemies,
-            state,
-            rng,
-        }
+    /// Creates a game that replays a recording exported by `export_replay`, feeding each input
+    /// back in on the tick it was originally made
+    pub fn replay(recording: &str) -> Result<Game, String> {
+        let recording = Recording::parse(recording).map_err(|err| err.to_string())?;
+        Self::replaying(&recording)
     }
-}
 
-#[wasm_bindgen]
-impl Game {
-    /// Ticks the game forward
+    /// Saves the entire game, enemies included, so it can be resumed later with `load`
+    pub fn save(&self) -> String {
+        serde_json::to_string(&SaveFile::new(self)).expect("Failed to serialize")
+    }
+
+    /// Resumes a game from a save created by `save`
+    pub fn load(save: &str) -> Result<Game, String> {
+        let save: SaveFile = serde_json::from_str(save).map_err(|err| err.to_string())?;
+        save.restore()
+    }
+
+    /// Exports every input made so far along with the game's seed
+    pub fn export_replay(&self) -> String {
+        self.recording.export()
+    }
+
+    /// The seed this game was created from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Ticks the game forward, returning true once the night has been won
     pub fn tick(&mut self) -> bool {
-        self.state.tick(&mut self.enemies, &mut self.rng)
+        while let Some(input) = self.replay.next_at(self.state.elapsed()) {
+            self.input(input);
+        }
+
+        let won = self.state.tick(&mut self.enemies, &mut self.rng);
+        self.state
+            .spawn_copies(&mut self.enemies, &self.registry, &mut self.rng);
+        if won {
+            self.campaign.win_night();
+            self.campaign.remember_habits(self.state.door_habits());
+        }
+
+        won
+    }
+
+    /// The campaign night currently being played
+    pub fn current_night(&self) -> u8 {
+        self.campaign.night()
+    }
+
+    /// Starts the next night of the campaign if the current one has been won, returning whether
+    /// a new night began
+    pub fn advance_night(&mut self) -> bool {
+        let mut campaign = self.campaign.clone();
+        if !campaign.advance() {
+            return false;
+        }
+
+        let seed = self.rng.next_u64();
+        match Self::start_night(campaign, seed, self.state.config.clone()) {
+            Ok(game) => {
+                *self = game;
+                true
+            }
+            Err(_) => false,
+        }
     }
 
     /// Gets the map context as a JsValue
@@ -91,7 +174,7 @@ impl Game {
 
     /// Gets the current time as an hour
     pub fn get_time(&self) -> u8 {
-        let hours = (self.state.ticks / TICKS_PER_HOUR) as u8;
+        let hours = self.state.hour() as u8;
         match hours {
             0 => 12,
             _ => hours,
@@ -117,17 +200,65 @@ impl Game {
 
     /// Toggles the camera state
     pub fn toggle_cameras(&mut self) {
-        self.state.toggle_cameras();
+        self.input(Input::ToggleCameras)
     }
 
     /// Close the left door
     pub fn toggle_left(&mut self) {
-        self.state.toggle_door(Door::Left)
+        self.input(Input::ToggleLeft)
     }
 
     /// Close the right door
     pub fn toggle_right(&mut self) {
-        self.state.toggle_door(Door::Right)
+        self.input(Input::ToggleRight)
+    }
+
+    /// Turns the light outside a door on or off
+    pub fn toggle_light(&mut self, door: Door) {
+        self.input(Input::ToggleLight(door))
+    }
+
+    /// Is the light outside a door on?
+    pub fn is_light_on(&self, door: Door) -> bool {
+        self.state.light_on(door)
+    }
+
+    /// The name of whoever the light outside a door shows standing at it, if the light is on and
+    /// anyone's there
+    pub fn light_reveals(&self, door: Door) -> Option<String> {
+        self.state
+            .light_reveals(door)
+            .map(|id| self.enemies[id].get_name().to_string())
+    }
+
+    /// Switches the camera view to a room, recorded so replays see the same camera changes
+    pub fn set_camera_view(&mut self, room: u64) {
+        let room = slotmap::KeyData::from_ffi(room);
+        self.input(Input::ViewCamera(room.into()))
+    }
+
+    /// The room the camera is pointed at, if the player has picked one yet
+    pub fn current_camera(&self) -> Option<u64> {
+        self.state.current_camera().map(|room| room.data().as_ffi())
+    }
+
+    /// How many ticks the player has spent looking at a room's camera tonight
+    pub fn camera_view_time(&self, room: u64) -> u64 {
+        let room = slotmap::KeyData::from_ffi(room);
+        self.state.view_time(room.into())
+    }
+
+    /// Takes every event that's happened since the last time this was called, as an array of
+    /// objects tagged by their `event` name. One-off effects like camera static and bangs on a
+    /// door are only ever reported here, so the frontend should watch for them in these events
+    pub fn drain_events(&mut self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.state.drain_events()).expect("Failed to serialize")
+    }
+
+    /// Takes every sound the player has heard since the last time this was called, as an array of
+    /// `{ sound, pan, loudness }` objects
+    pub fn drain_audio_cues(&mut self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.state.drain_audio_cues()).expect("Failed to serialize")
     }
 
     /// Is left door closed?
@@ -142,7 +273,7 @@ impl Game {
 
     /// Check the current power draw
     pub fn power_percent(&self) -> f64 {
-        ((self.state.power as f64 / INITIAL_POWER as f64) * 100.0).max(0.0)
+        self.state.power_percent()
     }
 
     /// Check if we're dead
@@ -162,13 +293,80 @@ impl Game {
     }
 }
 
+impl Game {
+    /// Create a new seeded game tuned by the given night configuration, which should be valid
+    pub fn with_night_config(seed: u64, config: NightConfig) -> Self {
+        Self::start_night(Campaign::default(), seed, config)
+            .expect("The built-in roster and given config are valid")
+    }
+
+    /// Create a seeded custom night where each enemy's aggression level is given by name,
+    /// failing if any name isn't on the roster or any level is above 20
+    pub fn with_levels(seed: u64, levels: BTreeMap<String, u8>) -> Result<Self, String> {
+        Self::start_night(Campaign::custom_night(levels), seed, NightConfig::default())
+    }
+
+    /// Starts the campaign's current night from a seed
+    fn start_night(campaign: Campaign, seed: u64, config: NightConfig) -> Result<Self, String> {
+        config.validate()?;
+
+        let mut rng = GameRng::seed_from_u64(seed);
+        let registry = BehaviorRegistry::default();
+        let enemies = campaign.roster(&registry)?;
+
+        let state = GameState::new(config.clone(), &mut rng)
+            .with_enemies(&enemies.keys().collect::<Vec<_>>(), &mut rng)
+            .with_habits(campaign.habits());
+
+        Ok(Self {
+            enemies,
+            registry,
+            state,
+            rng,
+            seed,
+            recording: Recording::new(seed, campaign.clone(), config),
+            replay: Replay::default(),
+            campaign,
+        })
+    }
+
+    /// Creates a game from a recording's seed that will replay all of its inputs as it ticks
+    pub fn replaying(recording: &Recording) -> Result<Self, String> {
+        let mut game = Self::start_night(
+            recording.campaign.clone(),
+            recording.seed,
+            recording.config.clone(),
+        )?;
+        game.replay = Replay::new(recording);
+
+        Ok(game)
+    }
+
+    /// Records a player input at the current tick and applies it to the game
+    fn input(&mut self, input: Input) {
+        self.recording.record(self.state.elapsed(), input);
+
+        match input {
+            Input::ToggleLeft => self.state.toggle_door(Door::Left),
+            Input::ToggleRight => self.state.toggle_door(Door::Right),
+            Input::ToggleCameras => self.state.toggle_cameras(),
+            Input::ToggleLight(door) => self.state.toggle_light(door),
+            Input::ViewCamera(room) => self.state.view_camera(room),
+        }
+    }
+}
+
 /// The game's internal state, responsible for keeping track of what enemies we have, where they
 /// are, if our doors are closed, what time it is, etc!
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameState {
-    /// The currently registered cooldown times for each enemy
-    cooldowns: HashMap<EnemyId, u64>,
+    /// When each enemy takes its next turn
+    schedule: Schedule,
     /// The current time
     ticks: u64,
+    /// How many ticks have actually gone by, which unlike the clock never runs backwards. Turns
+    /// and sabotage are timed against this so turning back the clock doesn't stretch them
+    elapsed: u64,
     /// The map as graph-like structure
     pub map: Map,
     /// The office room
@@ -176,56 +374,103 @@ pub struct GameState {
     /// Where enemies can be spawned
     pub spawn_points: Vec<RoomId>,
     /// Where enemies are located in the camera view
-    pub locations: HashMap<EnemyId, (f32, f32)>,
+    pub locations: SecondaryMap<EnemyId, (f32, f32)>,
     /// If the left door is closed
     left_door: bool,
     /// If the right door is closed
     right_door: bool,
     /// Are the cameras on
     cameras_on: bool,
+    /// If the light outside the left door is on
+    left_light: bool,
+    /// If the light outside the right door is on
+    right_light: bool,
+    /// The room the player's camera is pointed at, if they've picked one
+    camera_view: Option<RoomId>,
+    /// How many ticks the player has spent looking at each room's camera
+    view_times: SecondaryMap<RoomId, u64>,
     /// How much power is left
     power: i32,
     /// The current power draw (per tick)
     draw: i32,
     /// Are we dead?
     dead: bool,
-    /// What is our target ticks
-    ticks_needed_to_win: u64,
+    /// The power and time tuning for this night
+    config: NightConfig,
+    /// Cameras knocked out by enemies, and the tick they come back on
+    camera_outages: Vec<(RoomId, u64)>,
+    /// Doors jammed by enemies, and the tick they come unstuck
+    door_jams: Vec<(Door, u64)>,
+    /// How the player has kept the doors, tonight and over the campaign's earlier nights
+    habits: DoorHabits,
+    /// Doors being banged on, with how much power the banging drains a tick and the tick it stops
+    door_bangs: Vec<(Door, i32, u64)>,
+    /// Everything that's happened since the frontend last asked
+    events: Vec<GameEvent>,
+    /// The hour the frontend was last told it is
+    reported_hour: u64,
+    /// Sounds the player has heard since the frontend last asked
+    audio_cues: Vec<AudioCue>,
+    /// Enemies waiting to have a copy of themselves spawned in
+    pending_copies: Vec<EnemyId>,
+    /// Enemies that have been copied or are copies themselves, and so can't be copied again
+    copied: Vec<EnemyId>,
 }
 
 impl Default for GameState {
     fn default() -> Self {
+        Self::new(NightConfig::default(), &mut thread_rng())
+    }
+}
+
+/// A door's direction
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Door {
+    /// Left door
+    Left,
+    /// Right door
+    Right,
+}
+
+impl GameState {
+    /// Creates a fresh game state for a night tuned by `config`, generating the map layout from
+    /// the given Rng
+    pub fn new<RNG: Rng>(config: NightConfig, rng: &mut RNG) -> Self {
         let mut map = Map::default();
-        let mut rng = thread_rng();
-        let (office, spawn_points) = map.generate(&mut rng);
+        let (office, spawn_points) = map.generate(rng);
 
         GameState {
-            cooldowns: HashMap::default(),
+            schedule: Schedule::default(),
             ticks: 0,
+            elapsed: 0,
             map,
             office,
             spawn_points,
-            locations: HashMap::new(),
-            power: INITIAL_POWER,
+            locations: SecondaryMap::new(),
+            power: config.initial_power,
             left_door: false,
             right_door: false,
             cameras_on: false,
-            draw: DEFAULT_POWER_DRAW,
+            left_light: false,
+            right_light: false,
+            camera_view: None,
+            view_times: SecondaryMap::new(),
+            draw: config.default_power_draw,
             dead: false,
-            ticks_needed_to_win: HOURS_TO_WIN * TICKS_PER_HOUR,
+            config,
+            camera_outages: vec![],
+            door_jams: vec![],
+            habits: DoorHabits::default(),
+            door_bangs: vec![],
+            events: vec![],
+            reported_hour: 0,
+            audio_cues: vec![],
+            pending_copies: vec![],
+            copied: vec![],
         }
     }
-}
 
-/// A door's direction
-pub enum Door {
-    /// Left door
-    Left,
-    /// Right door
-    Right,
-}
-
-impl GameState {
     /// Registers a collection of enemies into the map
     pub fn with_enemies<RNG: Rng>(mut self, enemies: &[EnemyId], rng: &mut RNG) -> Self {
         for enemy in enemies {
@@ -237,55 +482,113 @@ impl GameState {
         self
     }
 
+    /// Carries over how the player kept the doors on earlier nights
+    pub fn with_habits(mut self, habits: DoorHabits) -> Self {
+        self.habits = habits;
+        self
+    }
+
+    #[cfg(test)]
+    /// Creates a night generated from a fixed seed, along with `N` enemy IDs that haven't been
+    /// placed anywhere yet and an Rng carrying on from the same seed, so test failures can be
+    /// reproduced
+    pub(crate) fn test_night<const N: usize>(seed: u64) -> (Self, [EnemyId; N], GameRng) {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut enemies: SlotMap<EnemyId, ()> = SlotMap::default();
+        let ids = std::array::from_fn(|_| enemies.insert(()));
+
+        (Self::new(NightConfig::default(), &mut rng), ids, rng)
+    }
+
     /// Generates a random location for an enemy and reassigns that in the lookup table
     pub fn generate_coords<RNG: Rng>(&mut self, enemy: EnemyId, rng: &mut RNG) -> (f32, f32) {
         let x = rng.gen_range(20..=230);
         let y = rng.gen_range(10..=90);
 
         self.locations.insert(enemy, (x as f32, y as f32));
-        self.locations[&enemy]
+        self.locations[enemy]
     }
 
     /// Returns an enemy's current location on the cameras
     pub fn get_coords(&self, enemy: &EnemyId) -> (f32, f32) {
-        self.locations[enemy]
+        self.locations[*enemy]
     }
 
     /// Ticks through all enemy behaviors if it's time
     pub fn tick<RNG: Rng>(&mut self, enemies: &mut SlotMap<EnemyId, Freak>, rng: &mut RNG) -> bool {
         self.ticks += 1;
+        self.elapsed += 1;
 
-        if self.ticks == self.ticks_needed_to_win {
+        if self.ticks == self.config.ticks_to_win() {
+            self.emit(GameEvent::Won);
             return true;
         }
+        // The clock can be turned back too, so this isn't always the next hour
+        let hour = self.hour();
+        if hour != self.reported_hour {
+            self.reported_hour = hour;
+            self.emit(GameEvent::HourChanged { hour });
+        }
 
+        let had_power = self.power > 0;
         self.power -= self.draw;
+        self.habits.record(self.left_door, self.right_door);
+        self.bang_doors();
         self.out_of_power();
+        self.clear_sabotage();
 
-        for (id, enemy) in enemies {
-            if let Some(time) = self.cooldowns.get(&id) {
-                if self.ticks % time == 0 {
-                    // It's action time
-                    enemy.tick(id, self, rng);
-                }
-            } else {
-                let new_cooldown = enemy.gen_cooldown(rng);
-                self.cooldowns.insert(id, new_cooldown);
+        if let (true, Some(room)) = (self.cameras_on, self.camera_view) {
+            *self.view_times.entry(room).unwrap().or_default() += 1;
+        }
+
+        for (id, enemy) in enemies.iter() {
+            if !self.schedule.is_scheduled(id) {
+                let next = self.elapsed + enemy.gen_cooldown(rng).max(1);
+                self.schedule.schedule(id, next);
+            }
+        }
+
+        while let Some(id) = self.schedule.pop_due(self.elapsed) {
+            if let Some(enemy) = enemies.get_mut(id) {
+                // It's action time
+                self.take_turn(id, enemy, rng);
             }
         }
 
-        if self.map.room_has_enemies(self.office.root) {
+        if had_power && self.power <= 0 {
+            self.emit(GameEvent::PowerOut);
+        }
+
+        if let Some(enemy) = self.get_enemy_in_room() {
+            if !self.dead {
+                self.emit(GameEvent::Killed { enemy });
+            }
             self.dead = true
         }
 
         false
     }
 
-    /// If power is below 0, opens the doors
+    /// If power is below 0, opens the doors and turns off the lights
     pub fn out_of_power(&mut self) -> bool {
         if self.power <= 0 {
+            for door in [Door::Left, Door::Right] {
+                if self.door_closed(door) {
+                    self.schedule
+                        .trigger(Trigger::DoorOpened(door), self.elapsed + 1);
+                    self.emit(GameEvent::DoorToggled {
+                        door,
+                        closed: false,
+                    });
+                }
+                if self.light_on(door) {
+                    self.emit(GameEvent::LightToggled { door, on: false });
+                }
+            }
             self.left_door = false;
             self.right_door = false;
+            self.left_light = false;
+            self.right_light = false;
 
             true
         } else {
@@ -293,37 +596,319 @@ impl GameState {
         }
     }
 
+    /// How many ticks into the night we are
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// How many ticks have gone by tonight, rewinds or not
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed
+    }
+
+    /// The current hour of the night, counting up from 0 at 12 AM
+    pub fn hour(&self) -> u64 {
+        self.ticks / self.config.ticks_per_hour
+    }
+
+    /// Are the cameras on?
+    pub fn cameras_on(&self) -> bool {
+        self.cameras_on
+    }
+
+    /// Points the camera at a room
+    pub fn view_camera(&mut self, room: RoomId) {
+        if self.map.0.contains_key(room) {
+            self.camera_view = Some(room);
+            self.schedule
+                .trigger(Trigger::CameraChanged, self.elapsed + 1);
+        }
+    }
+
+    /// The room the player's camera is pointed at, if they've picked one yet
+    pub fn current_camera(&self) -> Option<RoomId> {
+        self.camera_view
+    }
+
+    /// How many ticks the player has spent looking at a room's camera tonight
+    pub fn view_time(&self, room: RoomId) -> u64 {
+        self.view_times.get(room).copied().unwrap_or(0)
+    }
+
+    /// Is the player looking at this room through a working camera right now?
+    pub fn is_watched(&self, room: RoomId) -> bool {
+        self.cameras_on && self.camera_view == Some(room) && self.map.0[room].get_cams().is_some()
+    }
+
+    /// How much power is left
+    pub fn power(&self) -> i32 {
+        self.power
+    }
+
+    /// How much power is left, as a percentage of what the night started with
+    pub fn power_percent(&self) -> f64 {
+        ((self.power as f64 / self.config.initial_power as f64) * 100.0).max(0.0)
+    }
+
+    /// Drains a chunk of power all at once, opening the doors if that runs us out
+    pub fn drain_power(&mut self, amount: i32) {
+        self.power -= amount;
+        self.out_of_power();
+    }
+
     /// Toggles the cameras and sets the appropriate new power draw
     pub fn toggle_cameras(&mut self) {
         self.cameras_on = !self.cameras_on;
+        self.schedule
+            .trigger(Trigger::CameraChanged, self.elapsed + 1);
+        self.emit(GameEvent::CamerasToggled {
+            on: self.cameras_on,
+        });
 
         if self.cameras_on {
-            self.draw += CAMERA_ON_DRAW
+            self.draw += self.config.camera_on_draw
         } else {
-            self.draw -= CAMERA_ON_DRAW
+            self.draw -= self.config.camera_on_draw
         }
     }
 
     /// Toggles if a door is open or closed, affecting power draw respectively
     pub fn toggle_door(&mut self, direction: Door) {
-        if !self.out_of_power() {
-            let now_closed = match direction {
-                Door::Left => {
-                    self.left_door = !self.left_door;
-                    !self.left_door
-                }
-                Door::Right => {
-                    self.right_door = !self.right_door;
-                    !self.right_door
+        if !self.out_of_power() && !self.is_jammed(direction) {
+            self.flip_door(direction)
+        }
+    }
+
+    /// Turns the light outside a door on or off, affecting power draw respectively. Lights
+    /// can't be turned on without power
+    pub fn toggle_light(&mut self, door: Door) {
+        if self.out_of_power() {
+            return;
+        }
+
+        let light = match door {
+            Door::Left => &mut self.left_light,
+            Door::Right => &mut self.right_light,
+        };
+        *light = !*light;
+        let on = *light;
+
+        if on {
+            self.draw += self.config.light_draw
+        } else {
+            self.draw -= self.config.light_draw
+        }
+        self.emit(GameEvent::LightToggled { door, on });
+    }
+
+    /// Is the light outside the given door on?
+    pub fn light_on(&self, door: Door) -> bool {
+        match door {
+            Door::Left => self.left_light,
+            Door::Right => self.right_light,
+        }
+    }
+
+    /// The enemy the light outside a door shows standing at it, if the light is on and anyone's
+    /// there
+    pub fn light_reveals(&self, door: Door) -> Option<EnemyId> {
+        if !self.light_on(door) {
+            return None;
+        }
+
+        let room = match door {
+            Door::Left => self.office.left,
+            Door::Right => self.office.right,
+        };
+        self.map.enemies_in_room(room).first().copied()
+    }
+
+    /// Is the given door closed?
+    pub fn door_closed(&self, direction: Door) -> bool {
+        match direction {
+            Door::Left => self.left_door,
+            Door::Right => self.right_door,
+        }
+    }
+
+    /// How the player has kept the doors, tonight and over the campaign's earlier nights
+    pub fn door_habits(&self) -> DoorHabits {
+        self.habits
+    }
+
+    /// Is the given door jammed, unable to be toggled by the player?
+    pub fn is_jammed(&self, direction: Door) -> bool {
+        self.door_jams.iter().any(|(door, _)| *door == direction)
+    }
+
+    /// Knocks out a room's camera for the given amount of ticks
+    pub fn disable_camera_for(&mut self, room: RoomId, ticks: u64) {
+        self.map.disable_room_cam(room);
+        self.camera_outages.push((room, self.elapsed + ticks));
+        self.emit(GameEvent::CameraDisabled { room });
+    }
+
+    /// Forces a door open or closed and keeps the player from toggling it for the given amount of
+    /// ticks. Doors can't be jammed shut without power to hold them
+    pub fn jam_door(&mut self, direction: Door, closed: bool, ticks: u64) {
+        if closed && self.out_of_power() {
+            return;
+        }
+        if self.door_closed(direction) != closed {
+            self.flip_door(direction);
+        }
+
+        self.door_jams.retain(|(door, _)| *door != direction);
+        self.door_jams.push((direction, self.elapsed + ticks));
+    }
+
+    /// Sets an enemy's next turn to the given number of ticks from now, replacing whatever turn it
+    /// already had coming up
+    pub fn schedule_turn(&mut self, enemy: EnemyId, ticks: u64) {
+        self.schedule.schedule(enemy, self.elapsed + ticks.max(1));
+    }
+
+    /// Turns the clock back by the given amount of ticks. Only the clock goes back, enemies' turns
+    /// and any sabotage still happen when they would have
+    pub fn rewind(&mut self, ticks: u64) {
+        self.ticks = self.ticks.saturating_sub(ticks);
+    }
+
+    /// Queues up a copy of an enemy to be spawned in at the end of this tick. Each enemy can only
+    /// be copied once, and copies can't be copied
+    pub fn spawn_copy(&mut self, of: EnemyId) {
+        if !self.copied.contains(&of) {
+            self.copied.push(of);
+            self.pending_copies.push(of);
+        }
+    }
+
+    /// Starts banging on a door, draining `drain` power every tick for the next `ticks` ticks or
+    /// until the door is opened
+    pub fn bang_on_door(&mut self, door: Door, drain: i32, ticks: u64) {
+        self.door_bangs.push((door, drain, self.elapsed + ticks));
+        self.emit(GameEvent::DoorBanged { door });
+    }
+
+    /// Fills a room's camera with a burst of static
+    pub fn show_static(&mut self, room: RoomId) {
+        self.emit(GameEvent::CameraStatic { room });
+    }
+
+    /// Is an enemy banging on this door?
+    pub fn is_banging(&self, door: Door) -> bool {
+        self.door_bangs.iter().any(|(banged, _, _)| *banged == door)
+    }
+
+    /// Brings back any cameras and doors whose sabotage has worn off
+    fn clear_sabotage(&mut self) {
+        let ticks = self.elapsed;
+
+        let (over, ongoing): (Vec<_>, Vec<_>) = self
+            .camera_outages
+            .iter()
+            .partition(|(_, until)| *until <= ticks);
+        for (room, _) in over {
+            // Another outage on the same room may still be running
+            if !ongoing.iter().any(|(other, _)| *other == room) {
+                self.map.enable_room_cam(room);
+            }
+        }
+        self.camera_outages.retain(|(_, until)| *until > ticks);
+        self.door_jams.retain(|(_, until)| *until > ticks);
+    }
+
+    /// Has an enemy take its turn, then works out when its next one is
+    fn take_turn<RNG: Rng>(&mut self, id: EnemyId, enemy: &mut Freak, rng: &mut RNG) {
+        enemy.tick(id, self, rng);
+        self.schedule.wake_on(id, enemy.wake_on(id, self));
+
+        // The turn may have already pushed back the next one, such as by waiting at a door
+        if !self.schedule.is_scheduled(id) {
+            let next = self.elapsed + enemy.gen_cooldown(rng).max(1);
+            self.schedule.schedule(id, next);
+        }
+    }
+
+    /// Spawns in every queued copy at a random spawn point, building them from the registry the
+    /// originals were built from
+    pub fn spawn_copies<RNG: Rng>(
+        &mut self,
+        enemies: &mut SlotMap<EnemyId, Freak>,
+        registry: &BehaviorRegistry,
+        rng: &mut RNG,
+    ) {
+        for of in std::mem::take(&mut self.pending_copies) {
+            let copy = enemies.get(of).and_then(|enemy| enemy.duplicate(registry));
+            if let (Some(copy), Some(room)) = (copy, self.spawn_points.choose(rng)) {
+                let id = enemies.insert(copy);
+                self.copied.push(id);
+                self.map.register_enemy(id, *room);
+            }
+        }
+    }
+
+    /// Drains power for every door still being banged on, and stops the banging on doors that were
+    /// opened or whose banging ran its course
+    fn bang_doors(&mut self) {
+        let ticks = self.elapsed;
+
+        self.door_bangs.retain(|(door, _, until)| {
+            *until > ticks
+                && match door {
+                    Door::Left => self.left_door,
+                    Door::Right => self.right_door,
                 }
-            };
+        });
+        self.power -= self
+            .door_bangs
+            .iter()
+            .map(|(_, drain, _)| drain)
+            .sum::<i32>();
+    }
 
-            if now_closed {
-                self.draw -= POWER_DRAW_DOOR;
-            } else {
-                self.draw += POWER_DRAW_DOOR;
+    /// Opens a closed door or closes an open one, affecting power draw respectively
+    fn flip_door(&mut self, direction: Door) {
+        let now_closed = match direction {
+            Door::Left => {
+                self.left_door = !self.left_door;
+                !self.left_door
+            }
+            Door::Right => {
+                self.right_door = !self.right_door;
+                !self.right_door
             }
+        };
+        self.emit(GameEvent::DoorToggled {
+            door: direction,
+            closed: !now_closed,
+        });
+
+        if now_closed {
+            self.draw -= self.config.power_draw_door;
+            self.schedule
+                .trigger(Trigger::DoorOpened(direction), self.elapsed + 1);
+        } else {
+            self.draw += self.config.power_draw_door;
+        }
+    }
+
+    /// Queues up an event for the frontend to pick up, along with any sound it makes
+    pub fn emit(&mut self, event: GameEvent) {
+        if let Some(cue) = AudioCue::from_event(&event, self) {
+            self.audio_cues.push(cue);
         }
+        self.events.push(event);
+    }
+
+    /// Takes every event that's happened since the last time this was called
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Takes every sound the player has heard since the last time this was called
+    pub fn drain_audio_cues(&mut self) -> Vec<AudioCue> {
+        std::mem::take(&mut self.audio_cues)
     }
 
     /// Returns the first enemy in a room if it exists
@@ -344,29 +929,81 @@ impl GameState {
         }
         self.generate_coords(freak, rng);
         self.map.move_enemy_to(to, freak);
+        self.emit(GameEvent::EnemyMoved {
+            enemy: freak,
+            from: room,
+            to,
+        });
     }
 
     /// Attacks with a given enemy if possible
-    pub(crate) fn attack<RNG: Rng>(&mut self, attacker: EnemyId, rng: &mut RNG) {
+    pub(crate) fn attack<RNG: Rng>(&mut self, attacker: EnemyId, retreat: Retreat, rng: &mut RNG) {
         if self.attack_possible(attacker) {
             self.move_enemy(attacker, self.office.root, rng);
-        } else {
-            self.move_enemy(attacker, *self.spawn_points.choose(rng).unwrap(), rng)
+            return;
+        }
+
+        let Some(room) = self.map.get_enemy_room(attacker) else {
+            return;
+        };
+        self.emit(GameEvent::AttackBlocked {
+            enemy: attacker,
+            door: self.entrance_door(room),
+        });
+
+        match retreat {
+            Retreat::Spawn => {
+                self.move_enemy(attacker, *self.spawn_points.choose(rng).unwrap(), rng)
+            }
+            Retreat::Back(rooms) => {
+                let to = (0..rooms).fold(room, |room, _| self.further_from_office(room));
+                self.move_enemy(attacker, to, rng)
+            }
+            Retreat::Wait(ticks) => self.schedule_turn(attacker, ticks),
+            Retreat::OppositeEntrance => {
+                let to = match self.entrance_door(room) {
+                    Some(Door::Left) => self.office.right,
+                    Some(Door::Right) => self.office.left,
+                    None => room,
+                };
+                self.move_enemy(attacker, to, rng)
+            }
         }
     }
 
+    /// The neighbouring room furthest from the office, if it's any further than this one
+    fn further_from_office(&self, room: RoomId) -> RoomId {
+        let distance = |room| {
+            self.map
+                .generate_path(room, self.office.root)
+                .map_or(0, |path| path.len())
+        };
+
+        self.map.0[room]
+            .connections()
+            .iter()
+            .copied()
+            .filter(|next| distance(*next) > distance(room))
+            .max_by_key(|next| distance(*next))
+            .unwrap_or(room)
+    }
+
     /// Checks if an attack into the main office is possible currently for the given attacker
     fn attack_possible(&self, attacker: EnemyId) -> bool {
-        if let Some(room) = self.map.get_enemy_room(attacker) {
-            if room == self.office.left {
-                !self.left_door
-            } else if room == self.office.right {
-                !self.right_door
-            } else {
-                false
-            }
+        self.map
+            .get_enemy_room(attacker)
+            .and_then(|room| self.entrance_door(room))
+            .is_some_and(|door| !self.door_closed(door))
+    }
+
+    /// The door guarding an entrance to the office, if the room is one
+    pub fn entrance_door(&self, room: RoomId) -> Option<Door> {
+        if room == self.office.left {
+            Some(Door::Left)
+        } else if room == self.office.right {
+            Some(Door::Right)
         } else {
-            false
+            None
         }
     }
 }
@@ -374,9 +1011,21 @@ impl GameState {
 #[cfg(test)]
 mod tests {
     use rand::thread_rng;
-    use slotmap::SlotMap;
+    use slotmap::{Key, SlotMap};
 
-    use crate::{enemies::Freak, GameState};
+    use crate::{
+        campaign::Campaign,
+        config::NightConfig,
+        enemies::{
+            action::SideEffect,
+            effects::{JamDoor, SpawnCopy},
+            impls::generic::StraightPathBehavior,
+            roster::BehaviorRegistry,
+            Freak, Retreat,
+        },
+        events::GameEvent,
+        Door, Game, GameState,
+    };
 
     #[test]
     fn default_enemy_behavior_comes_closer_to_office() {
@@ -396,4 +1045,271 @@ mod tests {
         println!("{}", game.map);
         assert!(game.dead);
     }
+
+    #[test]
+    fn same_seed_plays_out_the_same_night() {
+        let mut game_1 = Game::with_seed(0xF3DD);
+        let mut game_2 = Game::with_seed(0xF3DD);
+
+        assert_eq!(game_1.render(), game_2.render());
+
+        for tick in 0..5000 {
+            if tick % 700 == 0 {
+                game_1.toggle_left();
+                game_2.toggle_left();
+            }
+
+            assert_eq!(game_1.tick(), game_2.tick());
+            assert_eq!(game_1.is_dead(), game_2.is_dead());
+        }
+
+        assert_eq!(game_1.render(), game_2.render());
+        assert_eq!(game_1.state.locations, game_2.state.locations);
+    }
+
+    #[test]
+    fn night_config_sets_the_length_of_the_night() {
+        let config = NightConfig {
+            ticks_per_hour: 10,
+            hours_to_win: 2,
+            ..Default::default()
+        };
+        let mut game = Game::with_night_config(7, config);
+
+        for _ in 1..20 {
+            assert!(!game.tick());
+        }
+
+        assert_eq!(game.get_time(), 1);
+        assert!(game.tick());
+    }
+
+    #[test]
+    fn lights_reveal_whoever_is_at_the_door() {
+        let (mut game, [lurker], _) = GameState::test_night(0x1167);
+        game.map.register_enemy(lurker, game.office.left);
+        let draw = game.draw;
+
+        assert_eq!(game.light_reveals(Door::Left), None);
+
+        game.toggle_light(Door::Left);
+        assert_eq!(game.draw, draw + game.config.light_draw);
+        assert_eq!(game.light_reveals(Door::Left), Some(lurker));
+
+        game.toggle_light(Door::Right);
+        assert_eq!(game.light_reveals(Door::Right), None);
+
+        game.power = 0;
+        game.out_of_power();
+        assert!(!game.light_on(Door::Left));
+        assert_eq!(game.light_reveals(Door::Left), None);
+    }
+
+    #[test]
+    fn unplayable_night_configs_are_rejected() {
+        for config in [
+            NightConfig {
+                ticks_per_hour: 0,
+                ..Default::default()
+            },
+            NightConfig {
+                hours_to_win: 0,
+                ..Default::default()
+            },
+            NightConfig {
+                ticks_per_hour: u64::MAX,
+                hours_to_win: 2,
+                ..Default::default()
+            },
+            NightConfig {
+                initial_power: 0,
+                ..Default::default()
+            },
+        ] {
+            assert!(Game::start_night(Campaign::default(), 7, config).is_err());
+        }
+    }
+
+    #[test]
+    fn toggles_and_the_clock_queue_events() {
+        let config = NightConfig {
+            ticks_per_hour: 10,
+            hours_to_win: 2,
+            ..Default::default()
+        };
+        let mut game = Game::with_night_config(7, config);
+
+        game.toggle_left();
+        for _ in 0..20 {
+            game.tick();
+        }
+
+        let events = game.state.drain_events();
+        assert_eq!(
+            events[0],
+            GameEvent::DoorToggled {
+                door: Door::Left,
+                closed: true
+            }
+        );
+        assert!(events.contains(&GameEvent::HourChanged { hour: 1 }));
+        assert_eq!(events.last(), Some(&GameEvent::Won));
+        assert!(game.state.drain_events().is_empty());
+    }
+
+    #[test]
+    fn rewinding_across_an_hour_reports_the_earlier_hour() {
+        let config = NightConfig {
+            ticks_per_hour: 10,
+            hours_to_win: 3,
+            ..Default::default()
+        };
+        let mut game = Game::with_night_config(7, config);
+        let mut hours = vec![];
+        let mut tick = |game: &mut Game, ticks| {
+            for _ in 0..ticks {
+                game.tick();
+            }
+            for event in game.state.drain_events() {
+                if let GameEvent::HourChanged { hour } = event {
+                    hours.push(hour);
+                }
+            }
+        };
+
+        tick(&mut game, 12);
+        game.state.rewind(5);
+        tick(&mut game, 1);
+        tick(&mut game, 5);
+
+        assert_eq!(hours, [1, 0, 1]);
+    }
+
+    #[test]
+    fn blocked_attacks_follow_the_enemys_retreat() {
+        let (mut game, [enemy], mut rng) = GameState::test_night(0x8AC4);
+        game.map.register_enemy(enemy, game.office.left);
+        game.toggle_door(Door::Left);
+
+        game.attack(enemy, Retreat::OppositeEntrance, &mut rng);
+        assert_eq!(game.map.get_enemy_room(enemy), Some(game.office.right));
+
+        game.toggle_door(Door::Right);
+        game.attack(enemy, Retreat::Wait(5), &mut rng);
+        assert_eq!(game.map.get_enemy_room(enemy), Some(game.office.right));
+        assert_eq!(game.schedule.next_turn(enemy), Some(game.elapsed + 5));
+
+        game.attack(enemy, Retreat::Back(2), &mut rng);
+        let room = game.map.get_enemy_room(enemy).unwrap();
+        let path = game.map.generate_path(room, game.office.root).unwrap();
+        assert!(path.len() > 2);
+    }
+
+    #[test]
+    fn side_effects_jam_doors_and_spawn_copies() {
+        let (game, [], mut rng) = GameState::test_night(0x5AB0);
+        let mut enemy_map = SlotMap::default();
+        let registry = BehaviorRegistry::default();
+        let spec = serde_json::json!({ "straight": {} });
+        let teller = Freak::from_spec("teller", 1000..2000, spec, &registry);
+        let teller = enemy_map.insert(teller.expect("Build enemy"));
+
+        let mut game = game.with_enemies(&[teller], &mut rng);
+
+        JamDoor {
+            door: Door::Left,
+            closed: true,
+            ticks: 10,
+        }
+        .do_something(&mut game);
+        game.toggle_door(Door::Left);
+        assert!(game.door_closed(Door::Left));
+
+        SpawnCopy(teller).do_something(&mut game);
+        for _ in 0..10 {
+            game.tick(&mut enemy_map, &mut rng);
+        }
+        game.spawn_copies(&mut enemy_map, &registry, &mut rng);
+
+        game.toggle_door(Door::Left);
+        assert!(!game.door_closed(Door::Left));
+        assert_eq!(enemy_map.len(), 2);
+
+        // Copies can't be copied again
+        for (id, _) in &enemy_map {
+            SpawnCopy(id).do_something(&mut game);
+        }
+        game.spawn_copies(&mut enemy_map, &registry, &mut rng);
+        assert_eq!(enemy_map.len(), 2);
+    }
+
+    #[test]
+    fn rewinding_the_clock_doesnt_hold_up_turns_or_stretch_sabotage() {
+        let (mut game, [], mut rng) = GameState::test_night(0x4E3D);
+        let mut enemy_map = SlotMap::default();
+        let waiter = enemy_map.insert(Freak::new(
+            "waiter",
+            10..11,
+            StraightPathBehavior::default(),
+        ));
+        game.map.register_enemy(waiter, game.spawn_points[0]);
+
+        game.tick(&mut enemy_map, &mut rng);
+        assert_eq!(game.schedule.next_turn(waiter), Some(11));
+        game.disable_camera_for(game.office.left, 10);
+
+        for _ in 0..5 {
+            game.tick(&mut enemy_map, &mut rng);
+        }
+        game.rewind(5);
+        for _ in 0..5 {
+            game.tick(&mut enemy_map, &mut rng);
+        }
+
+        assert_eq!(game.ticks(), 6);
+        assert_eq!(game.schedule.next_turn(waiter), Some(21));
+        assert!(game.map.0[game.office.left].get_cams().is_some());
+    }
+
+    #[test]
+    fn overlapping_outages_keep_the_camera_off_until_the_last_one_ends() {
+        let (mut game, [], mut rng) = GameState::test_night(0x0CA7);
+        let mut enemy_map = SlotMap::default();
+        let room = game.office.left;
+
+        game.disable_camera_for(room, 5);
+        game.disable_camera_for(room, 10);
+
+        for _ in 0..5 {
+            game.tick(&mut enemy_map, &mut rng);
+        }
+        assert!(game.map.0[room].get_cams().is_none());
+
+        for _ in 0..5 {
+            game.tick(&mut enemy_map, &mut rng);
+        }
+        assert!(game.map.0[room].get_cams().is_some());
+    }
+
+    #[test]
+    fn camera_views_are_tracked_and_replayed() {
+        let mut game = Game::with_seed(0xCA3);
+        let room = game.state.office.left;
+        let id = room.data().as_ffi();
+
+        game.set_camera_view(id);
+        game.toggle_cameras();
+        for _ in 0..10 {
+            game.tick();
+        }
+
+        assert_eq!(game.current_camera(), Some(id));
+        assert_eq!(game.camera_view_time(id), 10);
+
+        let mut replayed = Game::replay(&game.export_replay()).expect("Replay night");
+        for _ in 0..10 {
+            replayed.tick();
+        }
+        assert_eq!(replayed.camera_view_time(id), 10);
+    }
 }
@@ -6,7 +6,8 @@ use std::{
 };
 
 use rand::{seq::SliceRandom, Rng};
-use slotmap::{new_key_type, SlotMap};
+use serde::{Deserialize, Serialize};
+use slotmap::{new_key_type, SecondaryMap, SlotMap};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::enemies::EnemyId;
@@ -20,10 +21,11 @@ new_key_type! {
 }
 
 /// A contextual graph of all rooms
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Map(pub(crate) SlotMap<RoomId, Room>);
 
 /// The root room with distinct left and right 'hallways'
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RootRoomInfo {
     /// The root
     pub root: RoomId,
@@ -140,8 +142,19 @@ impl Map {
 
     /// Creates a path from one room to another room
     pub fn generate_path(&self, from: RoomId, to: RoomId) -> Option<Vec<RoomId>> {
+        self.generate_path_avoiding(from, to, &[])
+    }
+
+    /// Creates a path from one room to another room that never passes through any of the rooms to
+    /// avoid
+    pub fn generate_path_avoiding(
+        &self,
+        from: RoomId,
+        to: RoomId,
+        avoid: &[RoomId],
+    ) -> Option<Vec<RoomId>> {
         let mut search_queue = VecDeque::new();
-        let mut seen = HashSet::new();
+        let mut seen: HashSet<_> = avoid.iter().copied().filter(|room| *room != to).collect();
         let mut predecessors = HashMap::new();
 
         search_queue.push_front(from);
@@ -171,6 +184,27 @@ impl Map {
         None
     }
 
+    /// Finds how many rooms away from the given room every other room is, leaving out rooms that
+    /// can't reach it at all
+    pub fn distances_to(&self, to: RoomId) -> SecondaryMap<RoomId, usize> {
+        let mut distances = SecondaryMap::new();
+        let mut search_queue = VecDeque::from([to]);
+        distances.insert(to, 0);
+
+        while let Some(check_room) = search_queue.pop_front() {
+            let distance = distances[check_room] + 1;
+
+            for (room, info) in &self.0 {
+                if info.conencts_to.contains(&check_room) && !distances.contains_key(room) {
+                    distances.insert(room, distance);
+                    search_queue.push_back(room);
+                }
+            }
+        }
+
+        distances
+    }
+
     /// Disables a room's camera
     pub fn disable_room_cam(&mut self, room: RoomId) {
         self.0[room].disable_camera()
@@ -246,7 +280,7 @@ impl Display for Map {
 
 /// A room's information, such as who's in the room, is it disabled on the camera, and what rooms
 /// does it connect to
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Room {
     /// A Room's name
     name: String,
@@ -372,6 +406,29 @@ mod tests {
         assert_eq!(path, [room_a, room_c, room_d, room_b, room_g]);
     }
 
+    #[test]
+    fn distances_count_rooms_to_the_target() {
+        let mut map = Map::default();
+
+        let mut room_a = Room::default();
+        let mut room_b = Room::default();
+        let room_c = Room::default();
+        let room_d = map.0.insert(Room::default());
+
+        // a -> b -> c, with d off on its own
+        let room_c = map.0.insert(room_c);
+        room_b.connect_to(room_c);
+        let room_b = map.0.insert(room_b);
+        room_a.connect_to(room_b);
+        let room_a = map.0.insert(room_a);
+
+        let distances = map.distances_to(room_c);
+        assert_eq!(distances[room_c], 0);
+        assert_eq!(distances[room_b], 1);
+        assert_eq!(distances[room_a], 2);
+        assert!(!distances.contains_key(room_d));
+    }
+
     #[test]
     fn map_generation_is_good() {
         let mut map = Map::default();
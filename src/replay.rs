@@ -0,0 +1,148 @@
+//! Recording of every player input over a night, so a seeded game can be driven through the exact
+//! same night again
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{campaign::Campaign, config::NightConfig, map::RoomId, Door, Game};
+
+/// Anything the player can do that changes how a night plays out
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Input {
+    /// Toggled the left door
+    ToggleLeft,
+    /// Toggled the right door
+    ToggleRight,
+    /// Toggled the cameras
+    ToggleCameras,
+    /// Toggled the light outside a door
+    ToggleLight(Door),
+    /// Switched the camera view to a room
+    ViewCamera(RoomId),
+}
+
+/// A night's worth of player inputs along with the seed and config the night was generated from
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// The seed the recorded game was created with
+    pub seed: u64,
+    /// Which night of the campaign was recorded
+    pub campaign: Campaign,
+    /// The config the recorded night was tuned with
+    pub config: NightConfig,
+    /// Every input made, keyed by how many ticks had gone by when it was made. Unlike the clock,
+    /// that count never goes back when time is rewound
+    pub inputs: Vec<(u64, Input)>,
+}
+
+impl Recording {
+    /// Creates an empty recording for a campaign night created from `seed` and `config`
+    pub fn new(seed: u64, campaign: Campaign, config: NightConfig) -> Self {
+        Self {
+            seed,
+            campaign,
+            config,
+            inputs: vec![],
+        }
+    }
+
+    /// Records an input made once `elapsed` ticks had gone by
+    pub fn record(&mut self, elapsed: u64, input: Input) {
+        self.inputs.push((elapsed, input))
+    }
+
+    /// Serializes the recording so it can be attached to a bug report
+    pub fn export(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize")
+    }
+
+    /// Parses a recording previously created by [`Recording::export`]
+    pub fn parse(recording: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(recording)
+    }
+
+    /// Creates a game in replay mode from this recording and plays it out until the player either
+    /// wins or dies, returning the finished game
+    pub fn play(&self) -> Result<Game, String> {
+        let mut game = Game::replaying(self)?;
+
+        while !game.tick() && game.is_dead().is_none() {}
+
+        Ok(game)
+    }
+}
+
+/// Inputs from a recording that haven't been replayed yet
+#[derive(Default)]
+pub struct Replay(VecDeque<(u64, Input)>);
+
+impl Replay {
+    /// Creates a replay of all inputs in a recording
+    pub fn new(recording: &Recording) -> Self {
+        Self(recording.inputs.iter().copied().collect())
+    }
+
+    /// Pops the next input if it was made once `elapsed` ticks had gone by
+    pub fn next_at(&mut self, elapsed: u64) -> Option<Input> {
+        match self.0.front() {
+            Some((at, _)) if *at == elapsed => self.0.pop_front().map(|(_, input)| input),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Game;
+
+    use super::Recording;
+
+    #[test]
+    fn inputs_are_recorded_against_ticks_that_rewinds_cant_undo() {
+        let mut game = Game::with_seed(5);
+
+        for _ in 0..10 {
+            game.tick();
+        }
+        game.toggle_left();
+        game.state.rewind(5);
+        for _ in 0..5 {
+            game.tick();
+        }
+        game.toggle_left();
+
+        let recording = Recording::parse(&game.export_replay()).expect("Parse recording");
+        let at: Vec<_> = recording.inputs.iter().map(|(at, _)| *at).collect();
+        assert_eq!(at, [10, 15]);
+    }
+
+    #[test]
+    fn replay_reproduces_the_same_night() {
+        let mut game = Game::with_seed(1337);
+
+        for tick in 0..20_000u64 {
+            match tick % 1500 {
+                0 => game.toggle_left(),
+                300 => game.toggle_right(),
+                600 => game.toggle_cameras(),
+                900 => game.toggle_left(),
+                1200 => game.toggle_right(),
+                1400 => game.toggle_cameras(),
+                _ => {}
+            }
+
+            if game.tick() || game.is_dead().is_some() {
+                break;
+            }
+        }
+
+        let recording = Recording::parse(&game.export_replay()).expect("Parse recording");
+        let mut replayed = recording.play().expect("Replay recording");
+
+        assert_eq!(game.is_dead(), replayed.is_dead());
+        assert_eq!(game.get_time(), replayed.get_time());
+        assert_eq!(game.render(), replayed.render());
+        assert_eq!(game.export_replay(), replayed.export_replay());
+    }
+}
@@ -0,0 +1,99 @@
+//! Snapshots of an entire game, so a night can be stored away and resumed later
+
+use serde::{Deserialize, Serialize};
+use slotmap::SlotMap;
+
+use crate::{
+    campaign::Campaign,
+    enemies::{roster::BehaviorRegistry, EnemyId, Freak, SavedFreak},
+    replay::{Recording, Replay},
+    Game, GameRng, GameState,
+};
+
+/// Everything needed to resume a game exactly where it left off
+#[derive(Serialize, Deserialize)]
+pub struct SaveFile {
+    /// The seed the game was created from
+    seed: u64,
+    /// The random number generator, mid-stream
+    rng: GameRng,
+    /// The game's state
+    state: GameState,
+    /// Every enemy in the game, in the order they were added
+    enemies: Vec<(EnemyId, SavedFreak)>,
+    /// Inputs made so far, so a resumed game can still export a full replay
+    recording: Recording,
+    /// Progress through the campaign
+    campaign: Campaign,
+}
+
+impl SaveFile {
+    /// Snapshots a game
+    pub fn new(game: &Game) -> Self {
+        Self {
+            seed: game.seed,
+            rng: game.rng.clone(),
+            state: game.state.clone(),
+            enemies: game
+                .enemies
+                .iter()
+                .map(|(id, enemy)| (id, enemy.save()))
+                .collect(),
+            recording: game.recording.clone(),
+            campaign: game.campaign.clone(),
+        }
+    }
+
+    /// Rebuilds the game this snapshot was taken from
+    pub fn restore(self) -> Result<Game, String> {
+        self.state.config.validate()?;
+
+        let registry = BehaviorRegistry::default();
+        let mut enemies = SlotMap::default();
+
+        // Enemies are never removed, so inserting them back in order hands out the same IDs
+        for (id, saved) in self.enemies {
+            let restored = enemies.insert(Freak::restore(saved, &registry)?);
+            if restored != id {
+                return Err("Save has enemies out of order".to_string());
+            }
+        }
+
+        Ok(Game {
+            enemies,
+            registry,
+            state: self.state,
+            rng: self.rng,
+            seed: self.seed,
+            recording: self.recording,
+            replay: Replay::default(),
+            campaign: self.campaign,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Game;
+
+    #[test]
+    fn loaded_game_picks_up_where_it_left_off() {
+        let mut game = Game::with_seed(2024);
+        game.toggle_right();
+
+        for _ in 0..2500 {
+            game.tick();
+        }
+
+        let mut loaded = Game::load(&game.save()).expect("Load save");
+        assert_eq!(game.render(), loaded.render());
+
+        for _ in 0..2500 {
+            assert_eq!(game.tick(), loaded.tick());
+        }
+
+        assert_eq!(game.is_dead(), loaded.is_dead());
+        assert_eq!(game.render(), loaded.render());
+        assert_eq!(game.save(), loaded.save());
+    }
+}
@@ -0,0 +1,129 @@
+//! Scheduling of enemy turns. Every enemy has a tick its next turn falls on, re-rolled from its
+//! cooldown after each turn, and can ask to be woken early when something happens in the office
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use serde::{Deserialize, Serialize};
+use slotmap::SecondaryMap;
+
+use crate::{enemies::EnemyId, Door};
+
+/// Things happening in the office that can wake an enemy up before its next turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+    /// The player opened the given door
+    DoorOpened(Door),
+    /// The player turned the cameras on or off, or switched to a different one
+    CameraChanged,
+}
+
+/// When every enemy takes its next turn
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Upcoming turns, soonest first. Turns that were moved since being queued are left in and
+    /// skipped once they come up
+    turns: BinaryHeap<Reverse<(u64, EnemyId)>>,
+    /// The tick each enemy's next turn is on
+    next: SecondaryMap<EnemyId, u64>,
+    /// What each enemy has asked to be woken up by before its next turn
+    wake_ons: SecondaryMap<EnemyId, Vec<Trigger>>,
+}
+
+impl Schedule {
+    /// Does the enemy have a turn coming up?
+    pub fn is_scheduled(&self, enemy: EnemyId) -> bool {
+        self.next.contains_key(enemy)
+    }
+
+    /// The tick the enemy's next turn is on, if it has one coming up
+    pub fn next_turn(&self, enemy: EnemyId) -> Option<u64> {
+        self.next.get(enemy).copied()
+    }
+
+    /// Sets the enemy's next turn to the given tick, replacing any turn it already had
+    pub fn schedule(&mut self, enemy: EnemyId, tick: u64) {
+        self.next.insert(enemy, tick);
+        self.turns.push(Reverse((tick, enemy)));
+    }
+
+    /// Brings the enemy's next turn forward to the given tick, if it isn't already sooner
+    pub fn wake(&mut self, enemy: EnemyId, tick: u64) {
+        if self.next_turn(enemy).is_none_or(|next| next > tick) {
+            self.schedule(enemy, tick);
+        }
+    }
+
+    /// Takes the next enemy whose turn is due by the given tick, if there is one. The enemy is left
+    /// without a turn until it's scheduled again
+    pub fn pop_due(&mut self, tick: u64) -> Option<EnemyId> {
+        while let Some(Reverse((due, enemy))) = self.turns.peek().copied() {
+            if due > tick {
+                return None;
+            }
+
+            self.turns.pop();
+            if self.next.get(enemy) == Some(&due) {
+                self.next.remove(enemy);
+                return Some(enemy);
+            }
+        }
+
+        None
+    }
+
+    /// Has the enemy woken up by any of the triggers, until its next turn
+    pub fn wake_on(&mut self, enemy: EnemyId, triggers: Vec<Trigger>) {
+        if triggers.is_empty() {
+            self.wake_ons.remove(enemy);
+        } else {
+            self.wake_ons.insert(enemy, triggers);
+        }
+    }
+
+    /// Wakes every enemy waiting on the trigger, bringing their turn forward to the given tick
+    pub fn trigger(&mut self, trigger: Trigger, tick: u64) {
+        let woken: Vec<_> = self
+            .wake_ons
+            .iter()
+            .filter(|(_, triggers)| triggers.contains(&trigger))
+            .map(|(enemy, _)| enemy)
+            .collect();
+
+        for enemy in woken {
+            self.wake_ons.remove(enemy);
+            self.wake(enemy, tick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use crate::{enemies::EnemyId, Door};
+
+    use super::{Schedule, Trigger};
+
+    #[test]
+    fn turns_come_up_in_order_and_triggers_wake_enemies_early() {
+        let mut enemies: SlotMap<EnemyId, ()> = SlotMap::default();
+        let (a, b) = (enemies.insert(()), enemies.insert(()));
+        let mut schedule = Schedule::default();
+
+        schedule.schedule(a, 10);
+        schedule.schedule(b, 5);
+        assert_eq!(schedule.pop_due(4), None);
+        assert_eq!(schedule.pop_due(10), Some(b));
+        assert_eq!(schedule.pop_due(10), Some(a));
+        assert!(!schedule.is_scheduled(a));
+
+        schedule.schedule(a, 100);
+        schedule.wake_on(a, vec![Trigger::DoorOpened(Door::Left)]);
+        schedule.trigger(Trigger::DoorOpened(Door::Right), 20);
+        assert_eq!(schedule.next_turn(a), Some(100));
+
+        schedule.trigger(Trigger::DoorOpened(Door::Left), 20);
+        assert_eq!(schedule.pop_due(20), Some(a));
+        assert_eq!(schedule.pop_due(100), None);
+    }
+}